@@ -0,0 +1,355 @@
+// src/all_pairs.rs
+
+//! All-pairs BFS and pivot-sampled estimates of path length and closeness.
+
+use crate::analysis::Closeness;
+use crate::bfs::{with_pool, BfsEngine, BfsSummary, CHUNK};
+use crate::graph::Topology;
+use petgraph::graph::NodeIndex;
+use rand::rngs::StdRng;
+use rand::seq::index;
+use rand::SeedableRng;
+use rayon::prelude::*;
+use serde::Serialize;
+
+/// Everything derived from one BFS per node, shared by the path-based metrics.
+#[derive(Debug, Clone, Default)]
+pub struct AllPairs {
+    /// BFS aggregates per source, indexed by `NodeIndex::index()`.
+    pub per_node: Vec<BfsSummary>,
+    /// Sum of `1 / d` from each source to every other reached node.
+    pub harmonic: Vec<f64>,
+    /// `histogram[d]` = ordered (source, target) pairs at distance `d`,
+    /// including the `d = 0` self pairs.
+    pub histogram: Vec<u64>,
+}
+
+impl AllPairs {
+    /// Same value as `avg_shortest_path`: mean distance over all reached pairs.
+    pub fn avg_path(&self) -> f64 {
+        let total: u64 = self.per_node.iter().map(|s| s.sum).sum();
+        let pairs: u64 = self.per_node.iter().map(|s| s.reached as u64).sum();
+        total as f64 / pairs as f64
+    }
+
+    /// Same scores as `closeness_centrality`, indexed by `NodeIndex::index()`.
+    pub fn closeness(&self, kind: Closeness) -> Vec<f64> {
+        let n = self.per_node.len() as f64;
+        self.per_node
+            .iter()
+            .zip(&self.harmonic)
+            .map(|(s, &h)| kind.score(n, s.reached as f64, s.sum as f64, h))
+            .collect()
+    }
+
+    /// Eccentricity of each node within its component.
+    pub fn eccentricity(&self) -> Vec<usize> {
+        self.per_node.iter().map(|s| s.eccentricity).collect()
+    }
+}
+
+/// Run BFS from every node on `threads` worker threads (0 = one per core).
+///
+/// Sources are split into fixed chunks, results are reassembled in node order
+/// and every sum runs in a fixed order, so the output does not depend on the
+/// thread count or scheduling.
+pub fn all_pairs<G: Topology + Sync>(graph: &G, threads: usize) -> AllPairs {
+    let sources: Vec<NodeIndex> = graph.nodes().collect();
+    let sweep = || {
+        sources
+            .par_chunks(CHUNK)
+            .map(|chunk| {
+                let mut engine = BfsEngine::new();
+                let mut histogram: Vec<u64> = Vec::new();
+                let mut harmonic = Vec::with_capacity(chunk.len());
+                let summaries: Vec<BfsSummary> = chunk
+                    .iter()
+                    .map(|&start| {
+                        let mut inverse = 0.0;
+                        let summary = engine.run_with(graph, start, |_, d| {
+                            if histogram.len() <= d {
+                                histogram.resize(d + 1, 0);
+                            }
+                            histogram[d] += 1;
+                            if d > 0 {
+                                inverse += 1.0 / d as f64;
+                            }
+                        });
+                        harmonic.push(inverse);
+                        summary
+                    })
+                    .collect();
+                (summaries, harmonic, histogram)
+            })
+            .collect::<Vec<_>>()
+    };
+    let chunks = with_pool(threads, sweep);
+
+    let mut result = AllPairs::default();
+    for (summaries, harmonic, histogram) in chunks {
+        result.per_node.extend(summaries);
+        result.harmonic.extend(harmonic);
+        if result.histogram.len() < histogram.len() {
+            result.histogram.resize(histogram.len(), 0);
+        }
+        for (total, count) in result.histogram.iter_mut().zip(histogram) {
+            *total += count;
+        }
+    }
+    result
+}
+
+/// z-score of a two-sided 95% confidence interval.
+const Z_95: f64 = 1.96;
+
+/// How the path-based metrics are computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathMode {
+    /// BFS from every node.
+    #[default]
+    Exact,
+    /// BFS from `samples` random pivots drawn with `seed`.
+    Approximate {
+        /// Pivots to sample.
+        samples: usize,
+        /// Seed of the pivot sample.
+        seed: u64,
+    },
+    /// `Exact` while the BFS work of the exact pass, estimated as
+    /// `n · (n + m)`, is at most `max_exact_work`; `Approximate` above.
+    Auto {
+        /// Largest `n · (n + m)` computed exactly.
+        max_exact_work: u64,
+        /// Pivots to sample above the limit.
+        samples: usize,
+        /// Seed of the pivot sample.
+        seed: u64,
+    },
+}
+
+impl PathMode {
+    /// `Exact` or `Approximate`, with `Auto` decided for a graph of `nodes`
+    /// nodes and `edges` edges.
+    pub fn resolve(self, nodes: usize, edges: usize) -> PathMode {
+        let work = (nodes as u64).saturating_mul((nodes as u64).saturating_add(edges as u64));
+        match self {
+            PathMode::Auto { max_exact_work, .. } if work <= max_exact_work => PathMode::Exact,
+            PathMode::Auto { samples, seed, .. } => PathMode::Approximate { samples, seed },
+            mode => mode,
+        }
+    }
+}
+
+/// A point estimate with its 95% confidence interval; unbounded ends are
+/// infinite (`null` in JSON).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Estimate {
+    /// Point estimate.
+    pub value: f64,
+    /// Lower end of the interval.
+    pub lower: f64,
+    /// Upper end of the interval.
+    pub upper: f64,
+}
+
+/// Distances from the pivots to one node, accumulated over the pivots.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PivotSums {
+    /// Pivots that reach the node (including the node itself, if a pivot).
+    pub reached: u64,
+    /// Σ d over those pivots.
+    pub sum: u64,
+    /// Σ d² over those pivots.
+    pub squares: u64,
+    /// Σ 1/d over those pivots, excluding d = 0.
+    pub harmonic: f64,
+    /// Σ 1/d² over those pivots, excluding d = 0.
+    pub harmonic_squares: f64,
+}
+
+impl PivotSums {
+    fn add(&mut self, d: usize) {
+        self.reached += 1;
+        self.sum += d as u64;
+        self.squares += (d * d) as u64;
+        if d > 0 {
+            let inverse = 1.0 / d as f64;
+            self.harmonic += inverse;
+            self.harmonic_squares += inverse * inverse;
+        }
+    }
+
+    fn merge(&mut self, other: &PivotSums) {
+        self.reached += other.reached;
+        self.sum += other.sum;
+        self.squares += other.squares;
+        self.harmonic += other.harmonic;
+        self.harmonic_squares += other.harmonic_squares;
+    }
+}
+
+/// Path metrics estimated from BFS runs rooted at a random sample of pivots.
+#[derive(Debug, Clone, Default)]
+pub struct SampledPairs {
+    /// Nodes in the sampled graph.
+    pub node_count: usize,
+    /// Pivots used, in index order.
+    pub pivots: Vec<NodeIndex>,
+    /// Ratio estimate of `AllPairs::avg_path`.
+    pub avg_path: Option<Estimate>,
+    /// Pivot distances per node, indexed by `NodeIndex::index()`.
+    pub per_node: Vec<PivotSums>,
+}
+
+impl SampledPairs {
+    /// Eppstein–Wang estimate of `AllPairs::closeness`, indexed by `NodeIndex::index()`.
+    ///
+    /// Each node's reach count, distance sum and inverse-distance sum are
+    /// estimated as `n / k` times their totals over the pivots; intervals come
+    /// from the delta method with a finite-population correction.
+    pub fn closeness(&self, kind: Closeness) -> Vec<Estimate> {
+        let n = self.node_count as f64;
+        let k = self.pivots.len() as f64;
+        if k == 0.0 {
+            let unknown = Estimate { value: 0.0, lower: 0.0, upper: f64::INFINITY };
+            return vec![unknown; self.per_node.len()];
+        }
+        let scale = n / k;
+        // Variance factor of an estimated total n/k Σ z from per-pivot sample variance.
+        let total_variance = n * n * (1.0 - k / n) / k;
+        self.per_node
+            .iter()
+            .map(|p| {
+                let reached = scale * p.reached as f64;
+                let sum = scale * p.sum as f64;
+                let harmonic = scale * p.harmonic;
+                let value = kind.score(n, reached, sum, harmonic);
+                if k < 2.0 {
+                    return Estimate { value, lower: 0.0, upper: f64::INFINITY };
+                }
+                // Per-pivot sample (co)variances of d, [reached] and 1/d.
+                let covariance = |xy: f64, x: f64, y: f64| ((xy - x * y / k) / (k - 1.0)).max(0.0);
+                let var_sum = covariance(p.squares as f64, p.sum as f64, p.sum as f64);
+                let var_reached = covariance(p.reached as f64, p.reached as f64, p.reached as f64);
+                let cov = (p.sum as f64 - p.sum as f64 * p.reached as f64 / k) / (k - 1.0);
+                let var_harmonic = covariance(p.harmonic_squares, p.harmonic, p.harmonic);
+                let variance = match kind {
+                    // No pivot beyond the node itself: nothing bounds the score
+                    // unless every node was a pivot.
+                    Closeness::Classic | Closeness::WassermanFaust if sum <= 0.0 => {
+                        if total_variance > 0.0 { f64::INFINITY } else { 0.0 }
+                    }
+                    Closeness::Classic => {
+                        let g = -(n - 1.0) / (sum * sum);
+                        total_variance * g * g * var_sum
+                    }
+                    Closeness::WassermanFaust => {
+                        let r = reached - 1.0;
+                        let g_sum = -r * r / ((n - 1.0) * sum * sum);
+                        let g_reached = 2.0 * r / ((n - 1.0) * sum);
+                        total_variance
+                            * (g_sum * g_sum * var_sum
+                                + g_reached * g_reached * var_reached
+                                + 2.0 * g_sum * g_reached * cov)
+                    }
+                    Closeness::Harmonic => total_variance * var_harmonic / ((n - 1.0) * (n - 1.0)),
+                };
+                let se = variance.max(0.0).sqrt();
+                Estimate {
+                    value,
+                    lower: (value - Z_95 * se).max(0.0),
+                    upper: value + Z_95 * se,
+                }
+            })
+            .collect()
+    }
+}
+
+/// `k` of the `n` node indices, drawn uniformly without replacement with `seed`,
+/// in index order.
+pub(crate) fn sample_pivots(n: usize, k: usize, seed: u64) -> Vec<NodeIndex> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut pivots: Vec<NodeIndex> = index::sample(&mut rng, n, k.min(n))
+        .into_iter()
+        .map(NodeIndex::new)
+        .collect();
+    pivots.sort();
+    pivots
+}
+
+/// Estimate average path length and per-node pivot distances from `samples`
+/// pivots chosen uniformly without replacement using `seed`.
+///
+/// The average path is the ratio of summed distances to reached pairs over the
+/// pivots, with a normal-approximation interval and finite-population
+/// correction, so it collapses to the exact value when every node is a pivot.
+/// Results are deterministic for a given seed. With no pivots the average path
+/// is `None` and every closeness interval is unbounded.
+pub fn sampled_pairs<G: Topology + Sync>(
+    graph: &G,
+    samples: usize,
+    seed: u64,
+    threads: usize,
+) -> SampledPairs {
+    let n = graph.node_count();
+    let k = samples.min(n);
+    if k == 0 {
+        // No pivots: every node is known to reach at least itself, nothing more.
+        return SampledPairs {
+            node_count: n,
+            pivots: Vec::new(),
+            avg_path: None,
+            per_node: vec![PivotSums::default(); n],
+        };
+    }
+    let pivots = sample_pivots(n, k, seed);
+
+    let sweep = || {
+        pivots
+            .par_chunks(CHUNK)
+            .map(|chunk| {
+                let mut engine = BfsEngine::new();
+                let mut per_node = vec![PivotSums::default(); n];
+                let summaries: Vec<BfsSummary> = chunk
+                    .iter()
+                    .map(|&pivot| {
+                        engine.run_with(graph, pivot, |node, d| per_node[node.index()].add(d))
+                    })
+                    .collect();
+                (summaries, per_node)
+            })
+            .collect::<Vec<_>>()
+    };
+    let chunks = with_pool(threads, sweep);
+
+    let mut summaries = Vec::with_capacity(k);
+    let mut per_node = vec![PivotSums::default(); n];
+    for (chunk_summaries, chunk_per_node) in chunks {
+        summaries.extend(chunk_summaries);
+        for (total, sums) in per_node.iter_mut().zip(&chunk_per_node) {
+            total.merge(sums);
+        }
+    }
+
+    let (nf, kf) = (n as f64, k as f64);
+    let fpc = 1.0 - kf / nf;
+
+    // Ratio estimator R = Σx / Σy with x = distance sum, y = reached count.
+    let total: f64 = summaries.iter().map(|s| s.sum as f64).sum();
+    let reached: f64 = summaries.iter().map(|s| s.reached as f64).sum();
+    let ratio = total / reached;
+    let avg_path = if k > 1 {
+        let y_bar = reached / kf;
+        let residual: f64 = summaries
+            .iter()
+            .map(|s| (s.sum as f64 - ratio * s.reached as f64).powi(2))
+            .sum::<f64>()
+            / (kf - 1.0);
+        let se = (fpc * residual / kf).sqrt() / y_bar;
+        Estimate { value: ratio, lower: ratio - Z_95 * se, upper: ratio + Z_95 * se }
+    } else {
+        Estimate { value: ratio, lower: f64::NEG_INFINITY, upper: f64::INFINITY }
+    };
+
+    SampledPairs { node_count: n, pivots, avg_path: Some(avg_path), per_node }
+}
@@ -1,57 +1,638 @@
 // src/analysis.rs
 
-use crate::graph::{Graph, bfs_distances};
-use petgraph::algo::connected_components;
-use std::collections::HashMap;
+//! Degree, path, component, core, clustering and centrality metrics over `Topology`.
+
+use crate::bfs::BfsEngine;
+use crate::graph::{AreaGraph, Topology};
+use crate::period::Period;
+use itertools::Itertools;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::str::FromStr;
 
 /// Returns a map: degree → count of nodes with that degree.
-pub fn degree_distribution(graph: &Graph) -> HashMap<usize, usize> {
+pub fn degree_distribution<G: Topology>(graph: &G) -> HashMap<usize, usize> {
     let mut counts = HashMap::new();
-    for node in graph.node_indices() {
-        let deg = graph.neighbors(node).count();
+    for node in graph.nodes() {
+        let deg = graph.degree(node);
         *counts.entry(deg).or_default() += 1;
     }
     counts
 }
 
+/// Newman's degree assortativity: the Pearson correlation of the degrees at
+/// either end of an edge. `None` when every edge joins nodes of one degree
+/// (including graphs without edges), where the correlation is undefined.
+pub fn degree_assortativity<G: Topology>(graph: &G) -> Option<f64> {
+    // Each edge is seen from both ends, which makes the sums symmetric.
+    let (mut ends, mut sum, mut squares, mut products) = (0.0, 0.0, 0.0, 0.0);
+    for v in graph.nodes() {
+        let dv = graph.degree(v) as f64;
+        graph.for_each_neighbor(v, |u| {
+            let du = graph.degree(u) as f64;
+            ends += 1.0;
+            sum += dv;
+            squares += dv * dv;
+            products += dv * du;
+        });
+    }
+    if ends == 0.0 {
+        return None;
+    }
+    let mean = sum / ends;
+    let variance = squares / ends - mean * mean;
+    if variance <= f64::EPSILON * squares / ends {
+        return None;
+    }
+    Some((products / ends - mean * mean) / variance)
+}
+
+/// Average neighbour degree by degree: for each degree `k` with nodes, the
+/// mean degree of the neighbours of degree-`k` nodes, with the node count.
+/// Rising curves mean busy nodes attach to busy nodes.
+pub fn avg_neighbor_degree<G: Topology>(graph: &G) -> Vec<(usize, f64, usize)> {
+    let mut by_degree: BTreeMap<usize, (u64, usize)> = BTreeMap::new();
+    for v in graph.nodes() {
+        let mut neighbor_degrees = 0u64;
+        graph.for_each_neighbor(v, |u| neighbor_degrees += graph.degree(u) as u64);
+        let entry = by_degree.entry(graph.degree(v)).or_default();
+        entry.0 += neighbor_degrees;
+        entry.1 += 1;
+    }
+    by_degree
+        .into_iter()
+        .map(|(k, (total, nodes))| {
+            let mean = if k > 0 { total as f64 / (k * nodes) as f64 } else { 0.0 };
+            (k, mean, nodes)
+        })
+        .collect()
+}
+
 /// Computes the average shortest-path length (all-pairs) via BFS.
-pub fn avg_shortest_path(graph: &Graph) -> f64 {
+pub fn avg_shortest_path<G: Topology>(graph: &G) -> f64 {
+    let mut engine = BfsEngine::new();
     let mut total = 0u64;
     let mut pairs = 0u64;
-    for start in graph.node_indices() {
-        let dm = bfs_distances(graph, start);
-        for &d in dm.values() {
-            total += d as u64;
-            pairs += 1;
-        }
+    for start in graph.nodes() {
+        let summary = engine.run(graph, start);
+        total += summary.sum;
+        pairs += summary.reached as u64;
     }
     total as f64 / pairs as f64
 }
 
-/// Computes closeness centrality for all nodes, returns the top `n` highest.
-pub fn closeness_centrality(
-    graph: &Graph,
+/// Closeness variant used to score nodes, for a node that reaches `r` of the
+/// `n` nodes (itself included) at total distance `s`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Closeness {
+    /// `(n - 1) / s`. Inflated in small components.
+    Classic,
+    /// `(r - 1) / (n - 1) * (r - 1) / s`, the classic score within the
+    /// component scaled by the share of the graph it reaches.
+    #[default]
+    WassermanFaust,
+    /// The sum of `1 / d` over the other nodes, divided by `n - 1`;
+    /// unreachable nodes contribute 0.
+    Harmonic,
+}
+
+impl Closeness {
+    /// Score of one node from its BFS aggregates; `harmonic` is the sum of `1 / d`.
+    pub fn score(self, n: f64, reached: f64, sum: f64, harmonic: f64) -> f64 {
+        if n <= 1.0 {
+            return 0.0;
+        }
+        match self {
+            Closeness::Classic if sum > 0.0 => (n - 1.0) / sum,
+            Closeness::WassermanFaust if sum > 0.0 => {
+                (reached - 1.0) / (n - 1.0) * (reached - 1.0) / sum
+            }
+            Closeness::Harmonic => harmonic / (n - 1.0),
+            _ => 0.0,
+        }
+    }
+}
+
+impl FromStr for Closeness {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "classic" => Ok(Closeness::Classic),
+            "wasserman-faust" => Ok(Closeness::WassermanFaust),
+            "harmonic" => Ok(Closeness::Harmonic),
+            other => Err(format!(
+                "unknown closeness `{}` (expected classic, wasserman-faust or harmonic)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Closeness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Closeness::Classic => "classic",
+            Closeness::WassermanFaust => "wasserman-faust",
+            Closeness::Harmonic => "harmonic",
+        })
+    }
+}
+
+/// Computes classic closeness centrality for all nodes, returns the top `n` highest.
+pub fn closeness_centrality<G: Topology<Node = (Period, String)>>(
+    graph: &G,
     n: usize,
 ) -> Vec<((String, String), f64)> {
-    let mut scores: Vec<((String, String), f64)> = graph
-        .node_indices()
+    closeness_centrality_with(graph, Closeness::Classic, n)
+}
+
+/// Like `closeness_centrality`, but with the `kind` of closeness to compute.
+pub fn closeness_centrality_with<G: Topology<Node = (Period, String)>>(
+    graph: &G,
+    kind: Closeness,
+    n: usize,
+) -> Vec<((String, String), f64)> {
+    let mut engine = BfsEngine::new();
+    let total = graph.node_count() as f64;
+    let scores: Vec<f64> = graph
+        .nodes()
         .map(|node| {
-            let dm = bfs_distances(graph, node);
-            let sum: f64 = dm.values().map(|&d| d as f64).sum();
-            let score = if sum > 0.0 {
-                (graph.node_count() as f64 - 1.0) / sum
-            } else {
-                0.0
-            };
-            let (day, area) = &graph[node];
-            ((day.to_string(), area.clone()), score)
+            let mut harmonic = 0.0;
+            let summary = engine.run_with(graph, node, |_, d| {
+                if d > 0 {
+                    harmonic += 1.0 / d as f64;
+                }
+            });
+            kind.score(total, summary.reached as f64, summary.sum as f64, harmonic)
         })
         .collect();
-    scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-    scores.into_iter().take(n).collect()
+    top_scores(graph, &scores, n)
+}
+
+/// Returns the `n` highest of `scores` (indexed by `NodeIndex::index()`) with their labels.
+pub fn top_scores<G: Topology<Node = (Period, String)>>(
+    graph: &G,
+    scores: &[f64],
+    n: usize,
+) -> Vec<((String, String), f64)> {
+    top_nodes(graph, scores, n)
+        .into_iter()
+        .map(|node| {
+            let (day, area) = graph.node(node);
+            ((day.to_string(), area.clone()), scores[node.index()])
+        })
+        .collect()
+}
+
+/// Nodes with the `n` highest `scores`, best first.
+///
+/// Ties are broken by period, then area name, so rankings do not depend on
+/// input order.
+pub(crate) fn top_nodes<G: Topology<Node = (Period, String)>>(
+    graph: &G,
+    scores: &[f64],
+    n: usize,
+) -> Vec<NodeIndex> {
+    let mut ranked: Vec<NodeIndex> = graph.nodes().collect();
+    ranked.sort_by(|&a, &b| {
+        scores[b.index()]
+            .total_cmp(&scores[a.index()])
+            .then_with(|| graph.node(a).cmp(graph.node(b)))
+    });
+    ranked.truncate(n);
+    ranked
 }
 
 /// Returns the number of connected components in the graph.
-pub fn component_count(graph: &Graph) -> usize {
-    connected_components(graph)
+pub fn component_count<G: Topology>(graph: &G) -> usize {
+    let mut seen = vec![false; graph.node_count()];
+    let mut count = 0;
+    for start in graph.nodes() {
+        if !seen[start.index()] {
+            count += 1;
+            graph.bfs_visit(start, |node, _| seen[node.index()] = true);
+        }
+    }
+    count
+}
+
+/// Connected component of each node, indexed by `NodeIndex::index()`.
+/// Components are numbered from 0 in order of their lowest node index.
+pub fn component_membership<G: Topology>(graph: &G) -> Vec<usize> {
+    let mut membership = vec![usize::MAX; graph.node_count()];
+    let mut count = 0;
+    for start in graph.nodes() {
+        if membership[start.index()] == usize::MAX {
+            graph.bfs_visit(start, |node, _| membership[node.index()] = count);
+            count += 1;
+        }
+    }
+    membership
+}
+
+/// Time and area coverage of one connected component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentSpan {
+    /// Nodes in the component.
+    pub size: usize,
+    /// Earliest period among its nodes.
+    pub first: Period,
+    /// Latest period among its nodes.
+    pub last: Period,
+    /// Distinct periods among its nodes.
+    pub periods: usize,
+    /// Distinct areas among its nodes, by name.
+    pub areas: BTreeSet<String>,
+}
+
+/// Span of every component from `component_membership`, indexed by component.
+pub fn component_spans<G: Topology<Node = (Period, String)>>(
+    graph: &G,
+    membership: &[usize],
+) -> Vec<ComponentSpan> {
+    let count = membership.iter().max().map_or(0, |&c| c + 1);
+    let mut periods: Vec<BTreeSet<Period>> = vec![BTreeSet::new(); count];
+    let mut areas: Vec<BTreeSet<String>> = vec![BTreeSet::new(); count];
+    let mut sizes = vec![0usize; count];
+    for node in graph.nodes() {
+        let c = membership[node.index()];
+        let (period, area) = graph.node(node);
+        sizes[c] += 1;
+        periods[c].insert(*period);
+        areas[c].insert(area.clone());
+    }
+    periods
+        .into_iter()
+        .zip(areas)
+        .zip(sizes)
+        .map(|((periods, areas), size)| ComponentSpan {
+            size,
+            // Every component has at least one node.
+            first: *periods.first().unwrap(),
+            last: *periods.last().unwrap(),
+            periods: periods.len(),
+            areas,
+        })
+        .collect()
+}
+
+/// Distance extremes of one connected component.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ComponentShape {
+    /// Nodes in the component.
+    pub size: usize,
+    /// Largest eccentricity in the component.
+    pub diameter: usize,
+    /// Smallest eccentricity in the component.
+    pub radius: usize,
+    /// Nodes whose eccentricity equals the radius, in index order.
+    pub center: Vec<NodeIndex>,
+    /// Nodes whose eccentricity equals the diameter, in index order.
+    pub periphery: Vec<NodeIndex>,
+}
+
+/// Shape of every component from per-node component ids and eccentricities
+/// (e.g. `component_membership` and `AllPairs::eccentricity`), indexed by component.
+pub fn component_shapes(membership: &[usize], eccentricity: &[usize]) -> Vec<ComponentShape> {
+    let count = membership.iter().max().map_or(0, |&c| c + 1);
+    let mut shapes = vec![
+        ComponentShape { radius: usize::MAX, ..Default::default() };
+        count
+    ];
+    for (&c, &e) in membership.iter().zip(eccentricity) {
+        let shape = &mut shapes[c];
+        shape.size += 1;
+        shape.diameter = shape.diameter.max(e);
+        shape.radius = shape.radius.min(e);
+    }
+    for (i, (&c, &e)) in membership.iter().zip(eccentricity).enumerate() {
+        let shape = &mut shapes[c];
+        if e == shape.radius {
+            shape.center.push(NodeIndex::new(i));
+        }
+        if e == shape.diameter {
+            shape.periphery.push(NodeIndex::new(i));
+        }
+    }
+    shapes
+}
+
+/// k-core decomposition of a graph.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CoreDecomposition {
+    /// Core number of each node, indexed by `NodeIndex::index()`: the largest
+    /// `k` such that the node belongs to a subgraph of minimum degree `k`.
+    pub core: Vec<usize>,
+    /// Largest core number in the graph.
+    pub max_core: usize,
+    /// (k, nodes with core number exactly k) for every non-empty k-shell.
+    pub shells: Vec<(usize, usize)>,
+}
+
+/// Core numbers by the Batagelj–Zaversnik bucket algorithm, O(nodes + edges).
+pub fn core_decomposition<G: Topology>(graph: &G) -> CoreDecomposition {
+    let n = graph.node_count();
+    let mut degree: Vec<usize> = graph.nodes().map(|v| graph.degree(v)).collect();
+    let max_degree = degree.iter().copied().max().unwrap_or(0);
+    // Nodes sorted by current degree; `start[d]` is where degree-d nodes begin.
+    let mut start = vec![0usize; max_degree + 2];
+    for &d in &degree {
+        start[d + 1] += 1;
+    }
+    for d in 1..start.len() {
+        start[d] += start[d - 1];
+    }
+    let mut order = vec![0usize; n];
+    let mut position = vec![0usize; n];
+    let mut next = start.clone();
+    for v in 0..n {
+        position[v] = next[degree[v]];
+        order[position[v]] = v;
+        next[degree[v]] += 1;
+    }
+    // Peel nodes in order; a later neighbour with higher degree moves down one bucket.
+    for i in 0..n {
+        let v = order[i];
+        graph.for_each_neighbor(NodeIndex::new(v), |u| {
+            let u = u.index();
+            if degree[u] > degree[v] {
+                let du = degree[u];
+                let first = order[start[du]];
+                if first != u {
+                    order.swap(position[u], start[du]);
+                    position[first] = position[u];
+                    position[u] = start[du];
+                }
+                start[du] += 1;
+                degree[u] -= 1;
+            }
+        });
+    }
+    let max_core = degree.iter().copied().max().unwrap_or(0);
+    let shells = degree.iter().copied().counts().into_iter().sorted().collect();
+    CoreDecomposition { core: degree, max_core, shells }
+}
+
+/// Triangle and clustering statistics of a graph.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Clustering {
+    /// Triangles through each node, indexed by `NodeIndex::index()`.
+    pub triangles: Vec<usize>,
+    /// Local clustering coefficient of each node; 0 below degree 2.
+    pub local: Vec<f64>,
+    /// Mean of `local` over all nodes.
+    pub average: f64,
+    /// 3 × triangles / connected triples.
+    pub transitivity: f64,
+    /// Distinct triangles in the graph.
+    pub triangle_count: usize,
+}
+
+/// Counts triangles by marking each node's neighbours and scanning theirs.
+pub fn clustering<G: Topology>(graph: &G) -> Clustering {
+    let n = graph.node_count();
+    let mut marked = vec![false; n];
+    let mut neighbors = Vec::new();
+    let mut triangles = vec![0usize; n];
+    let mut local = vec![0.0; n];
+    let mut triples = 0u64;
+    for v in graph.nodes() {
+        neighbors.clear();
+        graph.for_each_neighbor(v, |u| neighbors.push(u));
+        neighbors.iter().for_each(|u| marked[u.index()] = true);
+        // Each triangle at `v` is seen once from each of its other two corners.
+        let mut closed = 0;
+        for &u in &neighbors {
+            graph.for_each_neighbor(u, |w| {
+                if marked[w.index()] {
+                    closed += 1;
+                }
+            });
+        }
+        neighbors.iter().for_each(|u| marked[u.index()] = false);
+        let k = neighbors.len();
+        triangles[v.index()] = closed / 2;
+        if k >= 2 {
+            let pairs = k * (k - 1) / 2;
+            local[v.index()] = triangles[v.index()] as f64 / pairs as f64;
+            triples += pairs as u64;
+        }
+    }
+    // Every triangle is counted once at each of its three corners.
+    let total: usize = triangles.iter().sum();
+    Clustering {
+        average: if n > 0 { local.iter().sum::<f64>() / n as f64 } else { 0.0 },
+        transitivity: if triples > 0 { total as f64 / triples as f64 } else { 0.0 },
+        triangle_count: total / 3,
+        triangles,
+        local,
+    }
+}
+
+/// Returns each area's strength (sum of its co-occurrence weights), highest first.
+pub fn area_strengths(graph: &AreaGraph) -> Vec<(String, usize)> {
+    let mut strengths: Vec<(String, usize)> = graph
+        .node_indices()
+        .map(|node| {
+            let strength = graph.edges(node).map(|e| *e.weight()).sum();
+            (graph[node].clone(), strength)
+        })
+        .collect();
+    strengths.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    strengths
+}
+
+/// Returns the `n` area pairs that share the most days, ties broken by name.
+pub fn top_cooccurrences(
+    graph: &AreaGraph,
+    n: usize,
+) -> Vec<((String, String), usize)> {
+    let mut pairs: Vec<((String, String), usize)> = graph
+        .edge_references()
+        .map(|e| {
+            let (a, b) = (&graph[e.source()], &graph[e.target()]);
+            let pair = if a <= b { (a.clone(), b.clone()) } else { (b.clone(), a.clone()) };
+            (pair, *e.weight())
+        })
+        .collect();
+    pairs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    pairs.into_iter().take(n).collect()
+}
+
+/// Stopping rule for the iterative centralities.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Iteration {
+    /// Stop once the summed absolute change of all scores is below `n * tolerance`.
+    pub tolerance: f64,
+    /// Give up after this many iterations.
+    pub max_iterations: usize,
+}
+
+impl Default for Iteration {
+    fn default() -> Self {
+        Iteration { tolerance: 1e-6, max_iterations: 1000 }
+    }
+}
+
+/// Scores of an iterative centrality, indexed by `NodeIndex::index()`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Centrality {
+    /// Score of each node.
+    pub scores: Vec<f64>,
+    /// Iterations run.
+    pub iterations: usize,
+    /// `false` if `max_iterations` ran out before the tolerance was met.
+    pub converged: bool,
+}
+
+/// Neighbours of each area with the co-occurrence weight of the edge.
+fn weighted_adjacency(graph: &AreaGraph) -> Vec<Vec<(usize, f64)>> {
+    let mut adjacency = vec![Vec::new(); graph.node_count()];
+    for e in graph.edge_references() {
+        let (a, b, w) = (e.source().index(), e.target().index(), *e.weight() as f64);
+        adjacency[a].push((b, w));
+        if a != b {
+            adjacency[b].push((a, w));
+        }
+    }
+    adjacency
+}
+
+/// Repeat `step` from `start` until the scores settle per `settings`.
+///
+/// A step that overflows to a non-finite score stops the iteration early,
+/// unconverged, keeping the last finite scores.
+fn iterate<F: FnMut(&[f64]) -> Vec<f64>>(
+    start: Vec<f64>,
+    settings: Iteration,
+    mut step: F,
+) -> Centrality {
+    let threshold = start.len() as f64 * settings.tolerance;
+    let mut scores = start;
+    for iteration in 1..=settings.max_iterations {
+        let next = step(&scores);
+        if next.iter().any(|x| !x.is_finite()) {
+            return Centrality { scores, iterations: iteration - 1, converged: false };
+        }
+        let change: f64 = next.iter().zip(&scores).map(|(a, b)| (a - b).abs()).sum();
+        scores = next;
+        if change < threshold {
+            return Centrality { scores, iterations: iteration, converged: true };
+        }
+    }
+    Centrality { scores, iterations: settings.max_iterations, converged: false }
+}
+
+/// Scale `scores` to unit Euclidean length (left as is if all zero).
+fn normalize_l2(scores: &mut [f64]) {
+    // Divide by the largest magnitude first so squaring huge scores cannot overflow.
+    let largest = scores.iter().fold(0.0_f64, |m, x| m.max(x.abs()));
+    if largest > 0.0 {
+        scores.iter_mut().for_each(|x| *x /= largest);
+    }
+    let norm = scores.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        scores.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+/// Weighted PageRank; scores sum to 1.
+///
+/// A walker follows an edge with probability proportional to its weight and,
+/// with probability `1 - damping` or from an isolated area, jumps to an area
+/// drawn from `personalization` (uniform if `None` or all zero).
+pub fn pagerank(
+    graph: &AreaGraph,
+    damping: f64,
+    personalization: Option<&[f64]>,
+    settings: Iteration,
+) -> Centrality {
+    let n = graph.node_count();
+    if n == 0 {
+        return Centrality { converged: true, ..Default::default() };
+    }
+    let adjacency = weighted_adjacency(graph);
+    let strength: Vec<f64> = adjacency
+        .iter()
+        .map(|nbrs| nbrs.iter().map(|&(_, w)| w).sum())
+        .collect();
+    let jump: Vec<f64> = match personalization {
+        Some(weights) if weights.iter().sum::<f64>() > 0.0 => {
+            let total: f64 = weights.iter().sum();
+            weights.iter().map(|w| w / total).collect()
+        }
+        _ => vec![1.0 / n as f64; n],
+    };
+    iterate(vec![1.0 / n as f64; n], settings, |scores| {
+        let dangling: f64 = (0..n).filter(|&v| strength[v] == 0.0).map(|v| scores[v]).sum();
+        let mut next: Vec<f64> = jump
+            .iter()
+            .map(|p| (1.0 - damping + damping * dangling) * p)
+            .collect();
+        for (v, nbrs) in adjacency.iter().enumerate() {
+            if strength[v] > 0.0 {
+                let share = damping * scores[v] / strength[v];
+                for &(u, w) in nbrs {
+                    next[u] += share * w;
+                }
+            }
+        }
+        next
+    })
+}
+
+/// Weighted eigenvector centrality with unit Euclidean norm.
+///
+/// Power iteration on `A + I`, which has the same leading eigenvector as the
+/// weighted adjacency matrix `A` but does not oscillate on bipartite graphs.
+pub fn eigenvector_centrality(graph: &AreaGraph, settings: Iteration) -> Centrality {
+    let n = graph.node_count();
+    let adjacency = weighted_adjacency(graph);
+    iterate(vec![1.0 / (n as f64).sqrt(); n], settings, |scores| {
+        let mut next = scores.to_vec();
+        for (v, nbrs) in adjacency.iter().enumerate() {
+            for &(u, w) in nbrs {
+                next[v] += w * scores[u];
+            }
+        }
+        normalize_l2(&mut next);
+        next
+    })
+}
+
+/// Weighted Katz centrality `x = alpha·A·x + beta`, scaled to unit Euclidean norm.
+///
+/// Converges only if `alpha` is below `1 / λ`, the inverse of the largest
+/// eigenvalue of the weighted adjacency matrix; see `largest_eigenvalue`.
+/// Above it the scores grow without bound, and the result is unconverged.
+pub fn katz_centrality(
+    graph: &AreaGraph,
+    alpha: f64,
+    beta: f64,
+    settings: Iteration,
+) -> Centrality {
+    let n = graph.node_count();
+    let adjacency = weighted_adjacency(graph);
+    let mut result = iterate(vec![0.0; n], settings, |scores| {
+        adjacency
+            .iter()
+            .map(|nbrs| beta + alpha * nbrs.iter().map(|&(u, w)| w * scores[u]).sum::<f64>())
+            .collect()
+    });
+    normalize_l2(&mut result.scores);
+    result
+}
+
+/// Largest eigenvalue of the weighted adjacency matrix, from the Rayleigh
+/// quotient of an eigenvector centrality result on the same graph.
+pub fn largest_eigenvalue(graph: &AreaGraph, eigenvector: &Centrality) -> f64 {
+    let x = &eigenvector.scores;
+    weighted_adjacency(graph)
+        .iter()
+        .enumerate()
+        .map(|(v, nbrs)| x[v] * nbrs.iter().map(|&(u, w)| w * x[u]).sum::<f64>())
+        .sum()
 }
@@ -0,0 +1,190 @@
+// src/betweenness.rs
+
+//! Exact and sampled Brandes betweenness of nodes and edges.
+
+use crate::all_pairs::sample_pivots;
+use crate::bfs::{with_pool, BfsScratch, CHUNK};
+use crate::graph::Topology;
+use crate::period::Period;
+use petgraph::graph::NodeIndex;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// An undirected edge as (smaller index, larger index).
+pub type EdgeKey = (NodeIndex, NodeIndex);
+
+/// An edge's endpoint labels, as (period, area) strings, with its score.
+pub type EdgeScore = (((String, String), (String, String)), f64);
+
+/// Node and edge betweenness from Brandes' algorithm.
+///
+/// Scores count unordered pairs of other nodes, so on a path `a–b–c` node `b`
+/// scores 1 and each edge scores 2. Sampled scores are scaled by `n / k` to
+/// estimate the exact values.
+#[derive(Debug, Clone, Default)]
+pub struct Betweenness {
+    /// Node scores, indexed by `NodeIndex::index()`.
+    pub nodes: Vec<f64>,
+    /// Edge scores for every edge on some shortest path from a source.
+    pub edges: HashMap<EdgeKey, f64>,
+    /// Sources used when sampled; `None` for the exact scores.
+    pub pivots: Option<usize>,
+}
+
+/// Exact betweenness: one Brandes pass from every node.
+pub fn betweenness<G: Topology + Sync>(graph: &G, threads: usize) -> Betweenness {
+    let sources: Vec<NodeIndex> = graph.nodes().collect();
+    brandes(graph, &sources, 0.5, threads)
+}
+
+/// Betweenness estimated from `samples` source pivots drawn with `seed`
+/// (the same pivots as `sampled_pairs` for equal arguments).
+pub fn sampled_betweenness<G: Topology + Sync>(
+    graph: &G,
+    samples: usize,
+    seed: u64,
+    threads: usize,
+) -> Betweenness {
+    let n = graph.node_count();
+    let pivots = sample_pivots(n, samples, seed);
+    if pivots.is_empty() {
+        return Betweenness { nodes: vec![0.0; n], edges: HashMap::new(), pivots: Some(0) };
+    }
+    let scale = 0.5 * n as f64 / pivots.len() as f64;
+    let mut result = brandes(graph, &pivots, scale, threads);
+    result.pivots = Some(pivots.len());
+    result
+}
+
+/// Per-worker buffers for single-source dependency accumulation.
+#[derive(Default)]
+struct Dependencies {
+    scratch: BfsScratch,
+    sigma: Vec<f64>,
+    delta: Vec<f64>,
+}
+
+impl Dependencies {
+    /// Brandes' forward BFS counting shortest paths, then the backward pass
+    /// adding each node's and edge's dependency on `source`.
+    fn accumulate<G: Topology>(
+        &mut self,
+        graph: &G,
+        source: NodeIndex,
+        nodes: &mut [f64],
+        edges: &mut HashMap<EdgeKey, f64>,
+    ) {
+        let Dependencies { scratch, sigma, delta } = self;
+        for &node in scratch.visited() {
+            sigma[node.index()] = 0.0;
+            delta[node.index()] = 0.0;
+        }
+        sigma.resize(graph.node_count(), 0.0);
+        delta.resize(graph.node_count(), 0.0);
+
+        scratch.begin(graph.node_count(), source);
+        sigma[source.index()] = 1.0;
+        while let Some((node, d)) = scratch.pop() {
+            graph.for_each_neighbor(node, |nbr| {
+                scratch.discover(nbr, d + 1);
+                if scratch.distance(nbr) == Some(d + 1) {
+                    sigma[nbr.index()] += sigma[node.index()];
+                }
+            });
+        }
+
+        // Predecessors are recovered from distances instead of stored lists.
+        for &node in scratch.visited().iter().rev() {
+            let d = scratch.distance(node).unwrap_or_default();
+            let mut dependency = 0.0;
+            graph.for_each_neighbor(node, |nbr| {
+                if scratch.distance(nbr) == Some(d + 1) {
+                    let share =
+                        sigma[node.index()] / sigma[nbr.index()] * (1.0 + delta[nbr.index()]);
+                    dependency += share;
+                    *edges.entry(edge_key(node, nbr)).or_default() += share;
+                }
+            });
+            delta[node.index()] = dependency;
+            if node != source {
+                nodes[node.index()] += dependency;
+            }
+        }
+    }
+}
+
+fn edge_key(a: NodeIndex, b: NodeIndex) -> EdgeKey {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+/// Sum the dependencies of `sources` in fixed chunks, reassembled in source
+/// order so results do not depend on the thread count, then multiply by `scale`.
+fn brandes<G: Topology + Sync>(
+    graph: &G,
+    sources: &[NodeIndex],
+    scale: f64,
+    threads: usize,
+) -> Betweenness {
+    let n = graph.node_count();
+    let sweep = || {
+        sources
+            .par_chunks(CHUNK)
+            .map(|chunk| {
+                let mut buffers = Dependencies::default();
+                let mut nodes = vec![0.0; n];
+                let mut edges = HashMap::new();
+                for &source in chunk {
+                    buffers.accumulate(graph, source, &mut nodes, &mut edges);
+                }
+                (nodes, edges)
+            })
+            .collect::<Vec<_>>()
+    };
+    let chunks = with_pool(threads, sweep);
+
+    let mut result = Betweenness {
+        nodes: vec![0.0; n],
+        ..Default::default()
+    };
+    for (nodes, edges) in chunks {
+        for (total, x) in result.nodes.iter_mut().zip(nodes) {
+            *total += x;
+        }
+        for (edge, x) in edges {
+            *result.edges.entry(edge).or_default() += x;
+        }
+    }
+    result.nodes.iter_mut().for_each(|x| *x *= scale);
+    result.edges.values_mut().for_each(|x| *x *= scale);
+    result
+}
+
+/// Returns the `n` highest-scoring edges with their endpoint labels, ties
+/// broken by the (period, area) of each endpoint.
+pub fn top_edges<G: Topology<Node = (Period, String)>>(
+    graph: &G,
+    edges: &HashMap<EdgeKey, f64>,
+    n: usize,
+) -> Vec<EdgeScore> {
+    let label = |node: NodeIndex| {
+        let (period, area) = graph.node(node);
+        (period.to_string(), area.clone())
+    };
+    let mut ranked: Vec<(EdgeKey, f64)> = edges
+        .iter()
+        .map(|(&(a, b), &score)| {
+            // Order endpoints by label so the key is independent of node indices.
+            if graph.node(a) <= graph.node(b) { ((a, b), score) } else { ((b, a), score) }
+        })
+        .collect();
+    ranked.sort_by(|(x, sx), (y, sy)| {
+        sy.total_cmp(sx)
+            .then_with(|| graph.node(x.0).cmp(graph.node(y.0)))
+            .then_with(|| graph.node(x.1).cmp(graph.node(y.1)))
+    });
+    ranked
+        .into_iter()
+        .take(n)
+        .map(|((a, b), score)| ((label(a), label(b)), score))
+        .collect()
+}
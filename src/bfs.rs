@@ -0,0 +1,144 @@
+// src/bfs.rs
+
+//! Dense, buffer-reusing BFS kernel behind all path-based metrics.
+
+use crate::graph::Topology;
+use petgraph::graph::NodeIndex;
+use rayon::ThreadPoolBuilder;
+
+/// Sources handed to one worker at a time by the parallel sweeps; each chunk
+/// reuses one set of BFS buffers.
+pub(crate) const CHUNK: usize = 64;
+
+/// Runs `f` on a pool of `threads` workers (0 = one per core); a pool that
+/// fails to start falls back to rayon's global pool.
+pub(crate) fn with_pool<R: Send>(threads: usize, f: impl FnOnce() -> R + Send) -> R {
+    match ThreadPoolBuilder::new().num_threads(threads).build() {
+        Ok(pool) => pool.install(f),
+        Err(_) => f(),
+    }
+}
+
+/// Reusable BFS buffers, indexed by `NodeIndex::index()`.
+///
+/// `begin` clears only the entries touched by the previous run, so repeated
+/// searches over the same graph cost O(reached) each instead of O(nodes).
+#[derive(Debug, Clone, Default)]
+pub struct BfsScratch {
+    dist: Vec<usize>,
+    queue: Vec<NodeIndex>,
+    head: usize,
+    marks: Vec<bool>,
+    marked: Vec<usize>,
+}
+
+impl BfsScratch {
+    /// Reset the previous run and seed a new one at `start` in a graph of `n` nodes.
+    pub fn begin(&mut self, n: usize, start: NodeIndex) {
+        for node in self.queue.drain(..) {
+            self.dist[node.index()] = usize::MAX;
+        }
+        for i in self.marked.drain(..) {
+            self.marks[i] = false;
+        }
+        self.dist.resize(n, usize::MAX);
+        self.head = 0;
+        self.dist[start.index()] = 0;
+        self.queue.push(start);
+    }
+
+    /// Next queued node and its distance, in BFS order.
+    pub fn pop(&mut self) -> Option<(NodeIndex, usize)> {
+        let node = *self.queue.get(self.head)?;
+        self.head += 1;
+        Some((node, self.dist[node.index()]))
+    }
+
+    /// Queue `node` at distance `d` unless it has already been reached.
+    pub fn discover(&mut self, node: NodeIndex, d: usize) {
+        if self.dist[node.index()] == usize::MAX {
+            self.dist[node.index()] = d;
+            self.queue.push(node);
+        }
+    }
+
+    /// Set auxiliary flag `i` (out of `len`); returns `false` if it was already set.
+    /// `CliqueGraph` uses this to expand each bucket once per search.
+    pub fn mark(&mut self, i: usize, len: usize) -> bool {
+        if self.marks.len() < len {
+            self.marks.resize(len, false);
+        }
+        if self.marks[i] {
+            return false;
+        }
+        self.marks[i] = true;
+        self.marked.push(i);
+        true
+    }
+
+    /// Distance of `node` in the last run, if it was reached.
+    pub fn distance(&self, node: NodeIndex) -> Option<usize> {
+        self.dist.get(node.index()).copied().filter(|&d| d != usize::MAX)
+    }
+
+    /// Nodes reached by the last run, in BFS order.
+    pub fn visited(&self) -> &[NodeIndex] {
+        &self.queue
+    }
+}
+
+/// Aggregates of one BFS, enough for path-length and closeness metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BfsSummary {
+    /// Sum of distances to every reached node.
+    pub sum: u64,
+    /// Reached nodes, including the source.
+    pub reached: usize,
+    /// Largest distance to a reached node.
+    pub eccentricity: usize,
+}
+
+/// Runs BFS from many sources over one graph, reusing its buffers across runs.
+#[derive(Debug, Clone, Default)]
+pub struct BfsEngine {
+    scratch: BfsScratch,
+}
+
+impl BfsEngine {
+    /// An engine with empty buffers; they grow to the graph on first use.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// BFS from `start`, returning only the aggregates.
+    pub fn run<G: Topology>(&mut self, graph: &G, start: NodeIndex) -> BfsSummary {
+        self.run_with(graph, start, |_, _| {})
+    }
+
+    /// Like `run`, also calling `visit(node, distance)` for every reached node.
+    pub fn run_with<G: Topology, F: FnMut(NodeIndex, usize)>(
+        &mut self,
+        graph: &G,
+        start: NodeIndex,
+        mut visit: F,
+    ) -> BfsSummary {
+        let mut summary = BfsSummary::default();
+        graph.bfs_with(start, &mut self.scratch, |node, d| {
+            summary.sum += d as u64;
+            summary.reached += 1;
+            summary.eccentricity = d;
+            visit(node, d);
+        });
+        summary
+    }
+
+    /// Distance of `node` from the source of the last `run`.
+    pub fn distance(&self, node: NodeIndex) -> Option<usize> {
+        self.scratch.distance(node)
+    }
+
+    /// Nodes reached by the last `run`, in BFS order.
+    pub fn visited(&self) -> &[NodeIndex] {
+        self.scratch.visited()
+    }
+}
@@ -0,0 +1,266 @@
+// src/bipartite.rs
+
+//! The day–area bipartite model, its projections and clustering.
+
+use crate::error::GraphError;
+use crate::graph::{read_entries, AreaGraph, GraphOptions, Topology};
+use crate::period::Period;
+use itertools::Itertools;
+use petgraph::graph::{NodeIndex, UnGraph};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+/// A node of the bipartite day–area graph. The day side holds one node per
+/// time bucket, so it is a week or month node under coarser bucketing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub enum BipartiteNode {
+    /// A day (or coarser time bucket).
+    Day(Period),
+    /// An area, by name.
+    Area(String),
+}
+
+impl BipartiteNode {
+    /// Whether the node is on the day side.
+    pub fn is_day(&self) -> bool {
+        matches!(self, BipartiteNode::Day(_))
+    }
+}
+
+/// Day nodes and area nodes, with an edge when the area has an incident that day.
+pub type BipartiteGraph = UnGraph<BipartiteNode, ()>;
+
+/// Day-level projection: edge weight = number of areas two days share.
+pub type DayGraph = UnGraph<Period, usize>;
+
+/// Build the bipartite graph from a (DAY, AREA_NAME) CSV, one day-side node per
+/// `options.bucket`.
+/// Unlike `build_graph`, the edge count is linear in the number of rows.
+///
+/// Temporal edges join a (bucket, area) node to a later one, which has no
+/// bipartite counterpart; a nonzero `options.temporal_lag` is rejected.
+pub fn build_bipartite_graph<P: AsRef<Path>>(
+    path: P,
+    options: &GraphOptions,
+) -> Result<BipartiteGraph, GraphError> {
+    let path = path.as_ref();
+    if options.temporal_lag > 0 {
+        return Err(GraphError::UnsupportedOption {
+            path: path.to_path_buf(),
+            option: "temporal_lag".to_string(),
+            reason: "the bipartite graph has no temporal edges".to_string(),
+        });
+    }
+    let entries = read_entries(path, options)?;
+    Ok(bipartite_from_pairs(entries.iter().map(|(day, area)| (*day, area.as_str()))))
+}
+
+/// Build the bipartite graph from the (day, area) labels of a clique graph.
+pub fn bipartite_projection<G: Topology<Node = (Period, String)>>(graph: &G) -> BipartiteGraph {
+    bipartite_from_pairs(graph.nodes().map(|node| {
+        let (period, area) = graph.node(node);
+        (*period, area.as_str())
+    }))
+}
+
+/// Build the bipartite graph from (day, area) pairs; duplicates are ignored.
+/// Day nodes come first in date order, then area nodes in name order.
+pub fn bipartite_from_pairs<'a, I>(pairs: I) -> BipartiteGraph
+where
+    I: IntoIterator<Item = (Period, &'a str)>,
+{
+    let pairs: Vec<(Period, &str)> = pairs.into_iter().sorted().dedup().collect();
+
+    let mut graph = BipartiteGraph::new_undirected();
+    let mut days: BTreeMap<Period, NodeIndex> = BTreeMap::new();
+    for &(day, _) in &pairs {
+        days.entry(day)
+            .or_insert_with(|| graph.add_node(BipartiteNode::Day(day)));
+    }
+    let area_names: BTreeSet<&str> = pairs.iter().map(|&(_, area)| area).collect();
+    let areas: BTreeMap<&str, NodeIndex> = area_names
+        .into_iter()
+        .map(|area| (area, graph.add_node(BipartiteNode::Area(area.to_string()))))
+        .collect();
+    for (day, area) in pairs {
+        graph.add_edge(days[&day], areas[area], ());
+    }
+    graph
+}
+
+/// Degree distributions of the two sides: (day degree → count, area degree → count).
+pub fn bipartite_degrees(
+    graph: &BipartiteGraph,
+) -> (HashMap<usize, usize>, HashMap<usize, usize>) {
+    let mut day_counts = HashMap::new();
+    let mut area_counts = HashMap::new();
+    for node in graph.node_indices() {
+        let deg = graph.neighbors(node).count();
+        let counts = if graph[node].is_day() { &mut day_counts } else { &mut area_counts };
+        *counts.entry(deg).or_default() += 1;
+    }
+    (day_counts, area_counts)
+}
+
+/// Project onto days: two days are linked with weight = number of shared areas.
+pub fn project_days(graph: &BipartiteGraph) -> DayGraph {
+    let mut days = DayGraph::new_undirected();
+    let mut idx_map: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+    for node in graph.node_indices() {
+        if let BipartiteNode::Day(day) = graph[node] {
+            idx_map.insert(node, days.add_node(day));
+        }
+    }
+    for (pair, weight) in shared_neighbors(graph, false) {
+        days.add_edge(idx_map[&pair.0], idx_map[&pair.1], weight);
+    }
+    days
+}
+
+/// Project onto areas: same weights as `graph::project_areas`.
+pub fn project_area_graph(graph: &BipartiteGraph) -> AreaGraph {
+    let mut areas = AreaGraph::new_undirected();
+    let mut idx_map: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+    for node in graph.node_indices() {
+        if let BipartiteNode::Area(area) = &graph[node] {
+            idx_map.insert(node, areas.add_node(area.clone()));
+        }
+    }
+    for (pair, weight) in shared_neighbors(graph, true) {
+        areas.add_edge(idx_map[&pair.0], idx_map[&pair.1], weight);
+    }
+    areas
+}
+
+/// Count common neighbours for every pair of area nodes (`area_side`) or day nodes.
+fn shared_neighbors(
+    graph: &BipartiteGraph,
+    area_side: bool,
+) -> BTreeMap<(NodeIndex, NodeIndex), usize> {
+    let mut weights = BTreeMap::new();
+    // Hubs are the opposite side: every day links the areas it contains.
+    for hub in graph.node_indices().filter(|&n| graph[n].is_day() == area_side) {
+        let mut members: Vec<NodeIndex> = graph.neighbors(hub).collect();
+        members.sort();
+        for (a, b) in members.iter().tuple_combinations() {
+            *weights.entry((*a, *b)).or_default() += 1;
+        }
+    }
+    weights
+}
+
+/// Latapy et al. bipartite clustering coefficient, indexed by `NodeIndex::index()`.
+///
+/// For a node `u`, averages `|N(u) ∩ N(v)| / |N(u) ∪ N(v)|` over every `v`
+/// at distance two; nodes with no such `v` score 0.
+pub fn bipartite_clustering(graph: &BipartiteGraph) -> Vec<f64> {
+    let degree: Vec<usize> = graph
+        .node_indices()
+        .map(|n| graph.neighbors(n).count())
+        .collect();
+    let mut shared: HashMap<NodeIndex, usize> = HashMap::new();
+    graph
+        .node_indices()
+        .map(|u| {
+            shared.clear();
+            for w in graph.neighbors(u) {
+                for v in graph.neighbors(w).filter(|&v| v != u) {
+                    *shared.entry(v).or_default() += 1;
+                }
+            }
+            if shared.is_empty() {
+                return 0.0;
+            }
+            let total: f64 = shared
+                .iter()
+                .map(|(v, &common)| {
+                    let union = degree[u.index()] + degree[v.index()] - common;
+                    common as f64 / union as f64
+                })
+                .sum();
+            total / shared.len() as f64
+        })
+        .collect()
+}
+
+/// Average bipartite clustering of (day nodes, area nodes).
+pub fn average_bipartite_clustering(graph: &BipartiteGraph) -> (f64, f64) {
+    let clustering = bipartite_clustering(graph);
+    let (mut day_sum, mut day_n, mut area_sum, mut area_n) = (0.0, 0usize, 0.0, 0usize);
+    for node in graph.node_indices() {
+        if graph[node].is_day() {
+            day_sum += clustering[node.index()];
+            day_n += 1;
+        } else {
+            area_sum += clustering[node.index()];
+            area_n += 1;
+        }
+    }
+    let avg = |sum: f64, n: usize| if n > 0 { sum / n as f64 } else { 0.0 };
+    (avg(day_sum, day_n), avg(area_sum, area_n))
+}
+
+/// Size, degree and clustering summary of a bipartite graph, as written to `metrics.json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BipartiteSummary {
+    /// Number of day-side nodes.
+    pub day_nodes: usize,
+    /// Number of area nodes.
+    pub area_nodes: usize,
+    /// Number of (day, area) edges.
+    pub edges: usize,
+    /// (degree, node count) of the day side, sorted by degree.
+    pub day_degrees: Vec<(usize, usize)>,
+    /// (degree, node count) of the area side, sorted by degree.
+    pub area_degrees: Vec<(usize, usize)>,
+    /// Average bipartite clustering of the day nodes.
+    pub day_clustering: f64,
+    /// Average bipartite clustering of the area nodes.
+    pub area_clustering: f64,
+    /// Edges of the area projection: area pairs sharing at least one day.
+    pub area_pairs: usize,
+}
+
+impl BipartiteSummary {
+    /// Summarizes `graph`.
+    pub fn of(graph: &BipartiteGraph) -> Self {
+        let (day_degrees, area_degrees) = bipartite_degrees(graph);
+        let (day_clustering, area_clustering) = average_bipartite_clustering(graph);
+        let day_nodes = graph.node_weights().filter(|n| n.is_day()).count();
+        BipartiteSummary {
+            day_nodes,
+            area_nodes: graph.node_count() - day_nodes,
+            edges: graph.edge_count(),
+            day_degrees: day_degrees.into_iter().sorted().collect(),
+            area_degrees: area_degrees.into_iter().sorted().collect(),
+            day_clustering,
+            area_clustering,
+            area_pairs: project_area_graph(graph).edge_count(),
+        }
+    }
+
+    /// Prints degree ranges, clustering and the area projection size, one line each.
+    pub fn print_text(&self) {
+        for (side, degrees) in [("Day", &self.day_degrees), ("Area", &self.area_degrees)] {
+            let (min, mean, max) = degree_range(degrees);
+            println!("{} degrees: min {}, mean {:.2}, max {}", side, min, mean, max);
+        }
+        println!(
+            "Avg bipartite clustering: days {:.4}, areas {:.4}",
+            self.day_clustering, self.area_clustering
+        );
+        println!("Area projection: {} area pairs share a day", self.area_pairs);
+    }
+}
+
+/// (min, mean, max) of a (degree, node count) distribution sorted by degree;
+/// zeros when it is empty.
+fn degree_range(distribution: &[(usize, usize)]) -> (usize, f64, usize) {
+    let nodes: usize = distribution.iter().map(|&(_, count)| count).sum();
+    if nodes == 0 {
+        return (0, 0.0, 0);
+    }
+    let total: usize = distribution.iter().map(|&(degree, count)| degree * count).sum();
+    (distribution[0].0, total as f64 / nodes as f64, distribution[distribution.len() - 1].0)
+}
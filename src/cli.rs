@@ -0,0 +1,258 @@
+// src/cli.rs
+
+use final_project::{
+    normalize_area, CentralityOptions, Closeness, ColumnMap, GraphOptions, Iteration, Metric,
+    ParseMode, PathMode, PropagationOptions, ReportOptions, TimeBucket,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+/// Command-line interface for the crime-graph pipeline.
+#[derive(Debug, Parser)]
+#[command(name = "final_project", about = "LA crime-graph analysis")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Clean a raw LAPD crime export into a (DAY, AREA_NAME) CSV
+    Ingest {
+        /// Raw LAPD export (e.g. data/lapd_crime_2020_present.csv)
+        #[arg(short, long)]
+        raw: PathBuf,
+        /// Where to write the unique (DAY, AREA_NAME) pairs
+        #[arg(short, long, default_value = "data/day_area.csv")]
+        output: PathBuf,
+        /// Also write the cleaned incident rows here
+        #[arg(long)]
+        clean_output: Option<PathBuf>,
+    },
+    /// Build the graph and print its size
+    Build {
+        #[command(flatten)]
+        input: InputArgs,
+        /// Graph model to build
+        #[arg(long, value_enum, default_value_t = Model::Clique)]
+        model: Model,
+    },
+    /// Compute the selected metrics and print them
+    Analyze {
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        analysis: AnalysisArgs,
+        /// Format used for stdout
+        #[arg(short, long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Compute the selected metrics and write them to the output directory
+    Export {
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        analysis: AnalysisArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Print and export the selected metrics in one run
+    Report {
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        analysis: AnalysisArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct InputArgs {
+    /// CSV with one (day, area) pair per row
+    #[arg(short, long, default_value = "data/day_area.csv")]
+    pub input: PathBuf,
+    /// Also link each area to itself in the next N buckets (0 = same-bucket edges only);
+    /// rejected by the bipartite model and the area metrics
+    #[arg(long, default_value_t = 0)]
+    pub temporal_lag: usize,
+    /// Time bucket per node: hour (needs the time column or timestamped dates), day, week
+    /// or month
+    #[arg(short, long, default_value_t = TimeBucket::Day)]
+    pub bucket: TimeBucket,
+    /// Header of the date column
+    #[arg(long, default_value = "DAY")]
+    pub day_column: String,
+    /// Header of the area column
+    #[arg(long, default_value = "AREA_NAME")]
+    pub area_column: String,
+    /// Header of the time-of-day column, read only for hourly buckets (optional when the
+    /// dates carry a time)
+    #[arg(long, default_value = "TIME_OCC")]
+    pub time_column: String,
+    /// Skip malformed rows and normalize area names instead of aborting
+    #[arg(long)]
+    pub lenient: bool,
+    /// Keep each bucket's clique implicit instead of materializing its edges (clique model
+    /// only)
+    #[arg(long)]
+    pub implicit: bool,
+}
+
+impl InputArgs {
+    /// Graph construction options selected on the command line.
+    pub fn graph_options(&self) -> GraphOptions {
+        GraphOptions {
+            temporal_lag: self.temporal_lag,
+            bucket: self.bucket,
+            columns: ColumnMap {
+                day: self.day_column.clone(),
+                area: self.area_column.clone(),
+                time: self.time_column.clone(),
+            },
+            mode: if self.lenient { ParseMode::Lenient } else { ParseMode::Strict },
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct AnalysisArgs {
+    /// How many nodes to keep in top-N rankings
+    #[arg(short = 'n', long, default_value_t = 5)]
+    pub top: usize,
+    /// Comma-separated metrics: degree, avg-path, closeness, components,
+    /// area-cooccurrence, bipartite, betweenness, area-centrality, clustering, communities,
+    /// label-propagation, k-core, eccentricity, assortativity, degree-fit
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        default_value = "degree,avg-path,closeness,components,area-cooccurrence"
+    )]
+    pub metrics: Vec<Metric>,
+    /// Worker threads for all-pairs path metrics (0 = one per core)
+    #[arg(short = 'j', long, default_value_t = 0)]
+    pub threads: usize,
+    /// Closeness variant: classic, wasserman-faust or harmonic
+    #[arg(long, default_value_t = Closeness::WassermanFaust)]
+    pub closeness: Closeness,
+    /// PageRank damping factor for area-centrality
+    #[arg(long, default_value_t = 0.85)]
+    pub damping: f64,
+    /// Comma-separated areas PageRank teleports to (default: all areas); each must occur in
+    /// the input
+    #[arg(long, value_delimiter = ',')]
+    pub personalize: Vec<String>,
+    /// Katz attenuation factor, below 1 / largest eigenvalue (default: 0.9 / largest eigenvalue)
+    #[arg(long)]
+    pub katz_alpha: Option<f64>,
+    /// Convergence tolerance for PageRank, eigenvector and Katz centrality
+    #[arg(long, default_value_t = 1e-6)]
+    pub tolerance: f64,
+    /// Iteration limit for PageRank, eigenvector and Katz centrality
+    #[arg(long, default_value_t = 1000)]
+    pub max_iterations: usize,
+    /// Louvain resolution for area communities (higher gives smaller communities)
+    #[arg(long, default_value_t = 1.0)]
+    pub resolution: f64,
+    /// Fewest nodes in the degree tail when choosing x_min for degree-fit
+    #[arg(long, default_value_t = 50)]
+    pub fit_min_tail: usize,
+    /// Label-propagation runs (seeds --seed, --seed + 1, ...) compared for stability
+    #[arg(long, default_value_t = 5)]
+    pub lp_runs: usize,
+    /// Sweep limit per label-propagation run
+    #[arg(long, default_value_t = 100)]
+    pub lp_max_sweeps: usize,
+    /// Exact all-pairs BFS, pivot sampling, or exact up to --max-exact-work
+    /// (applies to avg-path, closeness and betweenness)
+    #[arg(long, value_enum, default_value_t = Paths::Exact)]
+    pub path_mode: Paths,
+    /// Pivots sampled for approximate path metrics (at least 1)
+    #[arg(
+        long,
+        default_value_t = 256,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub samples: usize,
+    /// Seed for the pivot sample and label propagation
+    #[arg(long, default_value_t = 42)]
+    pub seed: u64,
+    /// Largest BFS work, nodes × (nodes + edges), that --path-mode auto computes exactly
+    #[arg(long, default_value_t = 50_000_000_000)]
+    pub max_exact_work: u64,
+}
+
+impl AnalysisArgs {
+    /// Report options selected on the command line, for input read in `mode`.
+    pub fn report_options(&self, mode: ParseMode) -> ReportOptions {
+        ReportOptions {
+            metrics: self.metrics.clone(),
+            top_n: self.top,
+            threads: self.threads,
+            closeness: self.closeness,
+            resolution: self.resolution,
+            fit_min_tail: self.fit_min_tail,
+            propagation: PropagationOptions {
+                seed: self.seed,
+                runs: self.lp_runs,
+                max_sweeps: self.lp_max_sweeps,
+            },
+            centrality: CentralityOptions {
+                damping: self.damping,
+                personalize: match mode {
+                    // Match the names the way the input's areas were read.
+                    ParseMode::Strict => self.personalize.clone(),
+                    ParseMode::Lenient => {
+                        self.personalize.iter().map(|area| normalize_area(area)).collect()
+                    }
+                },
+                katz_alpha: self.katz_alpha,
+                iteration: Iteration {
+                    tolerance: self.tolerance,
+                    max_iterations: self.max_iterations,
+                },
+            },
+            path_mode: match self.path_mode {
+                Paths::Exact => PathMode::Exact,
+                Paths::Approximate => PathMode::Approximate {
+                    samples: self.samples,
+                    seed: self.seed,
+                },
+                Paths::Auto => PathMode::Auto {
+                    max_exact_work: self.max_exact_work,
+                    samples: self.samples,
+                    seed: self.seed,
+                },
+            },
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct OutputArgs {
+    /// Directory that receives metrics.json and the CSV exports
+    #[arg(short, long, default_value = "report")]
+    pub out_dir: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Model {
+    /// One node per (day, area), fully connected within each day
+    Clique,
+    /// Day nodes and area nodes, linked when the area has an incident that day
+    Bipartite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Paths {
+    Exact,
+    Approximate,
+    Auto,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+}
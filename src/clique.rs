@@ -0,0 +1,137 @@
+// src/clique.rs
+
+//! The (bucket, area) graph with each bucket's clique stored implicitly.
+
+use crate::bfs::BfsScratch;
+use crate::error::GraphError;
+use crate::graph::{read_entries, GraphOptions, Topology};
+use crate::period::Period;
+use petgraph::graph::NodeIndex;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// The (bucket, area) graph with each bucket's clique stored implicitly.
+///
+/// Every node belongs to exactly one group (its time bucket) and is adjacent
+/// to all other members of that group, plus any explicit temporal links.
+/// Memory is linear in the number of nodes instead of quadratic per bucket,
+/// and BFS expands each group once instead of scanning every clique edge.
+/// Node indices match those of `graph_from_entries` for the same entries.
+#[derive(Debug, Clone, Default)]
+pub struct CliqueGraph {
+    nodes: Vec<(Period, String)>,
+    group_of: Vec<usize>,
+    groups: Vec<Vec<NodeIndex>>,
+    links: Vec<Vec<NodeIndex>>,
+    link_count: usize,
+}
+
+/// Build the implicit-clique graph from a CSV; see `build_graph_with`.
+pub fn build_clique_graph<P: AsRef<Path>>(
+    path: P,
+    options: &GraphOptions,
+) -> Result<CliqueGraph, GraphError> {
+    let entries = read_entries(path, options)?;
+    Ok(CliqueGraph::from_entries(&entries, options))
+}
+
+impl CliqueGraph {
+    /// Group entries by bucket and add temporal links per `options.temporal_lag`.
+    pub fn from_entries(entries: &[(Period, String)], options: &GraphOptions) -> Self {
+        let mut graph = CliqueGraph::default();
+        let mut idx_map: HashMap<(Period, String), NodeIndex> = HashMap::new();
+        let mut group_idx: HashMap<Period, usize> = HashMap::new();
+        for (period, area) in entries {
+            if idx_map.contains_key(&(*period, area.clone())) {
+                continue;
+            }
+            let idx = NodeIndex::new(graph.nodes.len());
+            let group = *group_idx.entry(*period).or_insert_with(|| {
+                graph.groups.push(Vec::new());
+                graph.groups.len() - 1
+            });
+            graph.nodes.push((*period, area.clone()));
+            graph.group_of.push(group);
+            graph.groups[group].push(idx);
+            graph.links.push(Vec::new());
+            idx_map.insert((*period, area.clone()), idx);
+        }
+
+        // In node order, so link order is the same from run to run.
+        for i in 0..graph.nodes.len() {
+            let (period, area) = graph.nodes[i].clone();
+            for lag in 1..=options.temporal_lag {
+                let later = period.offset(lag as u32);
+                if let Some(&other) = idx_map.get(&(later, area.clone())) {
+                    graph.links[i].push(other);
+                    graph.links[other.index()].push(NodeIndex::new(i));
+                    graph.link_count += 1;
+                }
+            }
+        }
+        graph
+    }
+
+    /// Number of buckets (implicit cliques).
+    pub fn group_count(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Members of the bucket that `node` belongs to, including `node` itself.
+    pub fn group_members(&self, node: NodeIndex) -> &[NodeIndex] {
+        &self.groups[self.group_of[node.index()]]
+    }
+}
+
+impl Topology for CliqueGraph {
+    type Node = (Period, String);
+
+    fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn edge_count(&self) -> usize {
+        let clique_edges: usize = self.groups.iter().map(|g| g.len() * (g.len() - 1) / 2).sum();
+        clique_edges + self.link_count
+    }
+
+    fn node(&self, node: NodeIndex) -> &(Period, String) {
+        &self.nodes[node.index()]
+    }
+
+    fn degree(&self, node: NodeIndex) -> usize {
+        self.group_members(node).len() - 1 + self.links[node.index()].len()
+    }
+
+    fn for_each_neighbor<F: FnMut(NodeIndex)>(&self, node: NodeIndex, mut f: F) {
+        for &member in self.group_members(node) {
+            if member != node {
+                f(member);
+            }
+        }
+        self.links[node.index()].iter().copied().for_each(f);
+    }
+
+    /// Group-aware BFS: the first member of a bucket to be dequeued reaches the
+    /// whole bucket, so later members skip it. Cost is linear in nodes + links.
+    fn bfs_with<F: FnMut(NodeIndex, usize)>(
+        &self,
+        start: NodeIndex,
+        scratch: &mut BfsScratch,
+        mut visit: F,
+    ) {
+        scratch.begin(self.nodes.len(), start);
+        while let Some((node, d)) = scratch.pop() {
+            visit(node, d);
+            let group = self.group_of[node.index()];
+            if scratch.mark(group, self.groups.len()) {
+                for &member in &self.groups[group] {
+                    scratch.discover(member, d + 1);
+                }
+            }
+            for &nbr in &self.links[node.index()] {
+                scratch.discover(nbr, d + 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,386 @@
+// src/community.rs
+
+//! Louvain and label-propagation communities, modularity and NMI.
+
+use crate::graph::Topology;
+use petgraph::graph::{NodeIndex, UnGraph};
+use petgraph::visit::EdgeRef;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::{BTreeMap, HashMap};
+
+/// Edge weights that modularity can be computed over.
+pub trait EdgeWeight {
+    /// The edge's weight; unweighted edges count 1.
+    fn weight(&self) -> f64;
+}
+
+/// Unweighted edges, as in `Graph`, count 1.
+impl EdgeWeight for () {
+    fn weight(&self) -> f64 {
+        1.0
+    }
+}
+
+/// Co-occurrence counts, as in `AreaGraph`.
+impl EdgeWeight for usize {
+    fn weight(&self) -> f64 {
+        *self as f64
+    }
+}
+
+/// A partition of a graph's nodes into communities.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Partition {
+    /// Community of each node, indexed by `NodeIndex::index()`. Communities are
+    /// numbered from 0 by decreasing size, ties by their lowest node index.
+    pub membership: Vec<usize>,
+    /// Modularity of `membership` at the resolution it was found with.
+    pub modularity: f64,
+    /// Aggregation levels Louvain ran before no move improved modularity.
+    pub levels: usize,
+}
+
+impl Partition {
+    /// Number of communities, 0 for an empty graph.
+    pub fn community_count(&self) -> usize {
+        self.membership.iter().max().map_or(0, |&c| c + 1)
+    }
+
+    /// Nodes of each community, in index order.
+    pub fn communities(&self) -> Vec<Vec<NodeIndex>> {
+        let mut communities = vec![Vec::new(); self.community_count()];
+        for (i, &c) in self.membership.iter().enumerate() {
+            communities[c].push(NodeIndex::new(i));
+        }
+        communities
+    }
+}
+
+/// Weighted adjacency lists; an undirected edge appears in both endpoint lists
+/// and a self-loop once, so a node's degree counts its self-loop twice.
+type Adjacency = Vec<Vec<(usize, f64)>>;
+
+fn adjacency<N, E: EdgeWeight>(graph: &UnGraph<N, E>) -> Adjacency {
+    let mut adj = vec![Vec::new(); graph.node_count()];
+    for e in graph.edge_references() {
+        let (a, b, w) = (e.source().index(), e.target().index(), e.weight().weight());
+        adj[a].push((b, w));
+        if a != b {
+            adj[b].push((a, w));
+        }
+    }
+    adj
+}
+
+fn degrees(adj: &Adjacency) -> Vec<f64> {
+    adj.iter()
+        .enumerate()
+        .map(|(i, nbrs)| {
+            nbrs.iter()
+                .map(|&(j, w)| if i == j { 2.0 * w } else { w })
+                .sum()
+        })
+        .collect()
+}
+
+/// Modularity of `membership` over the weighted graph, with `resolution`
+/// scaling the null-model term (1.0 is the standard definition).
+pub fn modularity<N, E: EdgeWeight>(
+    graph: &UnGraph<N, E>,
+    membership: &[usize],
+    resolution: f64,
+) -> f64 {
+    let adj = adjacency(graph);
+    modularity_of(&adj, &degrees(&adj), membership, resolution)
+}
+
+fn modularity_of(adj: &Adjacency, k: &[f64], membership: &[usize], resolution: f64) -> f64 {
+    let m2: f64 = k.iter().sum();
+    if m2 == 0.0 {
+        return 0.0;
+    }
+    let count = membership.iter().max().map_or(0, |&c| c + 1);
+    let mut internal = vec![0.0; count];
+    let mut total = vec![0.0; count];
+    for (i, nbrs) in adj.iter().enumerate() {
+        total[membership[i]] += k[i];
+        for &(j, w) in nbrs {
+            if membership[i] == membership[j] {
+                internal[membership[i]] += if i == j { 2.0 * w } else { w };
+            }
+        }
+    }
+    internal
+        .iter()
+        .zip(&total)
+        .map(|(inside, tot)| inside / m2 - resolution * (tot / m2).powi(2))
+        .sum()
+}
+
+/// Louvain community detection on a weighted undirected graph.
+///
+/// Nodes are visited in index order and join the neighbouring community with
+/// the largest modularity gain (staying put on ties), then communities are
+/// merged into single nodes and the process repeats until nothing moves. The
+/// result is deterministic for a given graph.
+pub fn louvain<N, E: EdgeWeight>(graph: &UnGraph<N, E>, resolution: f64) -> Partition {
+    let original = adjacency(graph);
+    let original_k = degrees(&original);
+    let mut membership: Vec<usize> = (0..graph.node_count()).collect();
+    let mut adj = original.clone();
+    let mut levels = 0;
+    loop {
+        let k = degrees(&adj);
+        let (local, moved) = move_nodes(&adj, &k, resolution);
+        if !moved {
+            break;
+        }
+        levels += 1;
+        let (local, count) = renumber(&local);
+        membership.iter_mut().for_each(|c| *c = local[*c]);
+        adj = aggregate(&adj, &local, count);
+    }
+    let (membership, _) = by_size(&membership);
+    Partition {
+        modularity: modularity_of(&original, &original_k, &membership, resolution),
+        membership,
+        levels,
+    }
+}
+
+/// One Louvain phase: local moves until a full sweep changes nothing.
+/// Returns each node's community and whether any node moved.
+fn move_nodes(adj: &Adjacency, k: &[f64], resolution: f64) -> (Vec<usize>, bool) {
+    let n = adj.len();
+    let m2: f64 = k.iter().sum();
+    let mut community: Vec<usize> = (0..n).collect();
+    let mut total: Vec<f64> = k.to_vec();
+    if m2 == 0.0 {
+        return (community, false);
+    }
+    // Weight from the current node to each neighbouring community.
+    let mut links = vec![0.0; n];
+    let mut is_touched = vec![false; n];
+    let mut touched: Vec<usize> = Vec::new();
+    let mut moved = false;
+    loop {
+        let mut changed = false;
+        for i in 0..n {
+            let current = community[i];
+            for &(j, w) in &adj[i] {
+                if j != i {
+                    let c = community[j];
+                    if !is_touched[c] {
+                        is_touched[c] = true;
+                        touched.push(c);
+                    }
+                    links[c] += w;
+                }
+            }
+            total[current] -= k[i];
+            let gain = |c: usize, links: &[f64]| links[c] - resolution * total[c] * k[i] / m2;
+            let mut best = current;
+            let mut best_gain = gain(current, &links);
+            for &c in &touched {
+                let g = gain(c, &links);
+                if g > best_gain + 1e-12 {
+                    best = c;
+                    best_gain = g;
+                }
+            }
+            total[best] += k[i];
+            community[i] = best;
+            if best != current {
+                changed = true;
+            }
+            for c in touched.drain(..) {
+                links[c] = 0.0;
+                is_touched[c] = false;
+            }
+        }
+        if !changed {
+            break;
+        }
+        moved = true;
+    }
+    (community, moved)
+}
+
+/// Collapse each community into one node, keeping internal weight as a self-loop.
+fn aggregate(adj: &Adjacency, community: &[usize], count: usize) -> Adjacency {
+    let mut weights: Vec<HashMap<usize, f64>> = vec![HashMap::new(); count];
+    for (i, nbrs) in adj.iter().enumerate() {
+        let a = community[i];
+        for &(j, w) in nbrs {
+            let b = community[j];
+            // Internal edges are listed from both ends; self-loops once.
+            let w = if a == b && i != j { w / 2.0 } else { w };
+            *weights[a].entry(b).or_default() += w;
+        }
+    }
+    weights
+        .into_iter()
+        .map(|row| {
+            let mut row: Vec<(usize, f64)> = row.into_iter().collect();
+            row.sort_by_key(|&(j, _)| j);
+            row
+        })
+        .collect()
+}
+
+/// Relabel communities 0.. in order of first appearance.
+fn renumber(community: &[usize]) -> (Vec<usize>, usize) {
+    let mut labels = HashMap::new();
+    let relabeled = community
+        .iter()
+        .map(|c| {
+            let next = labels.len();
+            *labels.entry(*c).or_insert(next)
+        })
+        .collect();
+    (relabeled, labels.len())
+}
+
+/// Relabel communities by decreasing size, ties by lowest member.
+fn by_size(community: &[usize]) -> (Vec<usize>, usize) {
+    let (community, count) = renumber(community);
+    let mut sizes = vec![0usize; count];
+    community.iter().for_each(|&c| sizes[c] += 1);
+    // After `renumber`, label order is lowest-member order.
+    let mut order: Vec<usize> = (0..count).collect();
+    order.sort_by(|&a, &b| sizes[b].cmp(&sizes[a]).then(a.cmp(&b)));
+    let mut rank = vec![0; count];
+    for (r, &c) in order.iter().enumerate() {
+        rank[c] = r;
+    }
+    (community.iter().map(|&c| rank[c]).collect(), count)
+}
+
+/// Result of one label-propagation run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LabelPropagation {
+    /// Final labels as communities; `levels` is 0.
+    pub partition: Partition,
+    /// Full sweeps over the nodes.
+    pub sweeps: usize,
+    /// `false` if `max_sweeps` ran out while labels were still changing.
+    pub converged: bool,
+}
+
+/// Asynchronous label propagation, seeded for reproducibility.
+///
+/// Every node starts with its own label. Each sweep visits the nodes in a
+/// random order and gives each the label most common among its neighbours,
+/// keeping its own label if that is among the most common and otherwise
+/// breaking ties at random. Runs until a sweep changes nothing. Cost per sweep
+/// is linear in the adjacency scanned, so it suits the full (bucket, area)
+/// graph, including `CliqueGraph`.
+pub fn label_propagation<G: Topology>(
+    graph: &G,
+    seed: u64,
+    max_sweeps: usize,
+) -> LabelPropagation {
+    let n = graph.node_count();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut labels: Vec<usize> = (0..n).collect();
+    let mut order: Vec<usize> = (0..n).collect();
+    let mut counts = vec![0usize; n];
+    let mut seen: Vec<usize> = Vec::new();
+    let mut best: Vec<usize> = Vec::new();
+    let mut sweeps = 0;
+    let mut converged = false;
+    while sweeps < max_sweeps {
+        sweeps += 1;
+        order.shuffle(&mut rng);
+        let mut changed = false;
+        for &i in &order {
+            graph.for_each_neighbor(NodeIndex::new(i), |nbr| {
+                let label = labels[nbr.index()];
+                if counts[label] == 0 {
+                    seen.push(label);
+                }
+                counts[label] += 1;
+            });
+            let top = seen.iter().map(|&label| counts[label]).max().unwrap_or(0);
+            best.clear();
+            best.extend(seen.iter().copied().filter(|&label| counts[label] == top));
+            for label in seen.drain(..) {
+                counts[label] = 0;
+            }
+            if best.is_empty() || best.contains(&labels[i]) {
+                continue;
+            }
+            best.sort_unstable();
+            labels[i] = best[rng.gen_range(0..best.len())];
+            changed = true;
+        }
+        if !changed {
+            converged = true;
+            break;
+        }
+    }
+    let (membership, _) = by_size(&labels);
+    LabelPropagation {
+        partition: Partition {
+            modularity: topology_modularity(graph, &membership),
+            membership,
+            levels: 0,
+        },
+        sweeps,
+        converged,
+    }
+}
+
+/// Modularity of `membership` over an unweighted `Topology`.
+pub fn topology_modularity<G: Topology>(graph: &G, membership: &[usize]) -> f64 {
+    let count = membership.iter().max().map_or(0, |&c| c + 1);
+    let mut internal = vec![0.0; count];
+    let mut total = vec![0.0; count];
+    for node in graph.nodes() {
+        let c = membership[node.index()];
+        total[c] += graph.degree(node) as f64;
+        graph.for_each_neighbor(node, |nbr| {
+            if membership[nbr.index()] == c {
+                internal[c] += 1.0;
+            }
+        });
+    }
+    let m2: f64 = total.iter().sum();
+    if m2 == 0.0 {
+        return 0.0;
+    }
+    internal
+        .iter()
+        .zip(&total)
+        .map(|(inside, tot)| inside / m2 - (tot / m2).powi(2))
+        .sum()
+}
+
+/// Normalized mutual information of two partitions of the same nodes:
+/// 1 for identical partitions up to relabeling, near 0 for unrelated ones.
+/// Sums run in label order, so the value is reproducible bit for bit.
+pub fn normalized_mutual_information(a: &[usize], b: &[usize]) -> f64 {
+    let n = a.len() as f64;
+    let mut joint: BTreeMap<(usize, usize), f64> = BTreeMap::new();
+    let mut left: BTreeMap<usize, f64> = BTreeMap::new();
+    let mut right: BTreeMap<usize, f64> = BTreeMap::new();
+    for (&x, &y) in a.iter().zip(b) {
+        *joint.entry((x, y)).or_default() += 1.0;
+        *left.entry(x).or_default() += 1.0;
+        *right.entry(y).or_default() += 1.0;
+    }
+    let entropy = |counts: &BTreeMap<usize, f64>| -> f64 {
+        counts.values().map(|&c| -(c / n) * (c / n).ln()).sum()
+    };
+    let (h_a, h_b) = (entropy(&left), entropy(&right));
+    if h_a + h_b == 0.0 {
+        return 1.0;
+    }
+    let mutual: f64 = joint
+        .iter()
+        .map(|(&(x, y), &c)| (c / n) * (c * n / (left[&x] * right[&y])).ln())
+        .sum();
+    2.0 * mutual / (h_a + h_b)
+}
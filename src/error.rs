@@ -0,0 +1,198 @@
+// src/error.rs
+
+//! The error type returned when an input CSV cannot be read.
+
+use crate::quality::DataQuality;
+use itertools::Itertools;
+use std::{error::Error, fmt, path::PathBuf};
+
+/// Errors raised while reading an input CSV and building a graph from it.
+///
+/// Every variant carries the input file; row-level variants also carry the
+/// 1-based line number and the offending value.
+#[derive(Debug)]
+pub enum GraphError {
+    /// The file could not be opened or a row could not be read as CSV.
+    Csv {
+        /// Input file.
+        path: PathBuf,
+        /// 1-based line of the failing record, when csv reports one.
+        line: Option<u64>,
+        /// The underlying csv error.
+        source: csv::Error,
+    },
+    /// A date cell did not parse as `YYYY-MM-DD`.
+    DateParse {
+        /// Input file.
+        path: PathBuf,
+        /// 1-based line of the row.
+        line: u64,
+        /// Header of the date column.
+        column: String,
+        /// The cell as read.
+        value: String,
+        /// The chrono parse error.
+        source: chrono::ParseError,
+    },
+    /// A time cell did not parse as `HHMM` or `HH:MM`.
+    TimeParse {
+        /// Input file.
+        path: PathBuf,
+        /// 1-based line of the row.
+        line: u64,
+        /// Header of the time column.
+        column: String,
+        /// The cell as read.
+        value: String,
+    },
+    /// Hourly buckets were requested, but the file has no time column and the
+    /// date cell carries no time of day.
+    MissingTime {
+        /// Input file.
+        path: PathBuf,
+        /// 1-based line of the row.
+        line: u64,
+        /// Header of the date column.
+        column: String,
+        /// The date cell as read.
+        value: String,
+        /// Header of the time column that was looked for.
+        time_column: String,
+    },
+    /// A required header is absent.
+    MissingColumn {
+        /// Input file.
+        path: PathBuf,
+        /// The header that was looked for.
+        column: String,
+        /// Headers the file does have, in file order.
+        available: Vec<String>,
+    },
+    /// The file has a header but no data rows.
+    EmptyInput {
+        /// Input file.
+        path: PathBuf,
+    },
+    /// Lenient mode skipped every data row; `quality` says why.
+    AllRowsRejected {
+        /// Input file.
+        path: PathBuf,
+        /// Row counts by skip reason.
+        quality: Box<DataQuality>,
+    },
+    /// An option was set that the requested build cannot honour.
+    UnsupportedOption {
+        /// Input file.
+        path: PathBuf,
+        /// Name of the option, as in `GraphOptions` or the report options.
+        option: String,
+        /// Why it cannot be honoured.
+        reason: String,
+    },
+    /// An option named areas that never occur in the input.
+    UnknownAreas {
+        /// Input file.
+        path: PathBuf,
+        /// Name of the option.
+        option: String,
+        /// Names matching no area, in option order.
+        unknown: Vec<String>,
+        /// Areas of the input, in name order.
+        available: Vec<String>,
+    },
+}
+
+impl GraphError {
+    /// Wraps a CSV error, taking the line number from its position if it has one.
+    pub(crate) fn csv(path: &std::path::Path, source: csv::Error) -> Self {
+        GraphError::Csv {
+            path: path.to_path_buf(),
+            line: source.position().map(|pos| pos.line()),
+            source,
+        }
+    }
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::Csv { path, line: Some(line), source } => {
+                // `csv::Error` already names itself ("CSV error: ...") where relevant.
+                write!(f, "{}:{}: {}", path.display(), line, source)
+            }
+            GraphError::Csv { path, line: None, source } => {
+                write!(f, "{}: {}", path.display(), source)
+            }
+            GraphError::DateParse { path, line, column, value, source } => write!(
+                f,
+                "{}:{}: invalid date `{}` in column {} ({}; expected YYYY-MM-DD, \
+                 optionally followed by HH:MM[:SS])",
+                path.display(),
+                line,
+                value,
+                column,
+                source
+            ),
+            GraphError::TimeParse { path, line, column, value } => write!(
+                f,
+                "{}:{}: invalid time `{}` in column {} (expected HHMM or HH:MM)",
+                path.display(),
+                line,
+                value,
+                column
+            ),
+            GraphError::MissingTime { path, line, column, value, time_column } => write!(
+                f,
+                "{}:{}: hourly buckets need a time of day, but `{}` in column {} is a plain \
+                 date and there is no {} column",
+                path.display(),
+                line,
+                value,
+                column,
+                time_column
+            ),
+            GraphError::MissingColumn { path, column, available } => write!(
+                f,
+                "{}: missing required column `{}`; available headers: {}",
+                path.display(),
+                column,
+                available.join(", ")
+            ),
+            GraphError::EmptyInput { path } => {
+                write!(f, "{}: no data rows after the header", path.display())
+            }
+            GraphError::AllRowsRejected { path, quality } => write!(
+                f,
+                "{}: all {} data rows were rejected ({})",
+                path.display(),
+                quality.rows_read,
+                quality
+                    .skipped
+                    .iter()
+                    .map(|(reason, count)| format!("{}: {}", reason, count))
+                    .join(", ")
+            ),
+            GraphError::UnsupportedOption { path, option, reason } => {
+                write!(f, "{}: option {} is not supported: {}", path.display(), option, reason)
+            }
+            GraphError::UnknownAreas { path, option, unknown, available } => write!(
+                f,
+                "{}: option {} names areas not in the input: {}; available areas: {}",
+                path.display(),
+                option,
+                unknown.join(", "),
+                available.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for GraphError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GraphError::Csv { source, .. } => Some(source),
+            GraphError::DateParse { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
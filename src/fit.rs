@@ -0,0 +1,361 @@
+// src/fit.rs
+
+//! Maximum-likelihood fits of the degree tail and likelihood-ratio comparisons.
+
+use serde::Serialize;
+use std::f64::consts::SQRT_2;
+
+/// p-value below which a likelihood-ratio comparison names the better model.
+pub const SIGNIFICANCE: f64 = 0.1;
+
+/// Search interval for the power-law exponent.
+const ALPHA_RANGE: (f64, f64) = (1.001, 20.0);
+
+/// Discrete power law `p(x) = x^-α / ζ(α, x_min)`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PowerLaw {
+    /// Exponent `α`.
+    pub alpha: f64,
+    /// Log-likelihood of the tail under the fit.
+    pub log_likelihood: f64,
+    /// Kolmogorov–Smirnov distance between the tail and the fitted CDF.
+    pub ks: f64,
+}
+
+/// Discrete exponential `p(x) = (1 - e^-λ) e^(-λ (x - x_min))`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Exponential {
+    /// Rate `λ`.
+    pub lambda: f64,
+    /// Log-likelihood of the tail under the fit.
+    pub log_likelihood: f64,
+    /// Kolmogorov–Smirnov distance between the tail and the fitted CDF.
+    pub ks: f64,
+}
+
+/// Log-normal with mass `P(x ≤ X < x + 1)` at each integer, truncated at `x_min`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogNormal {
+    /// Mean of `ln x`.
+    pub mu: f64,
+    /// Standard deviation of `ln x`.
+    pub sigma: f64,
+    /// Log-likelihood of the tail under the fit.
+    pub log_likelihood: f64,
+    /// Kolmogorov–Smirnov distance between the tail and the fitted CDF.
+    pub ks: f64,
+}
+
+/// Vuong's likelihood-ratio test of `first` against `second` on the tail.
+///
+/// A positive `log_ratio` favours `first`; `favored` names the winner only
+/// when `p_value` is below [`SIGNIFICANCE`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Comparison {
+    /// Name of the first model, e.g. `power-law`.
+    pub first: &'static str,
+    /// Name of the second model.
+    pub second: &'static str,
+    /// Log-likelihood of `first` minus that of `second`.
+    pub log_ratio: f64,
+    /// `log_ratio` over its standard deviation; infinite when the pointwise
+    /// ratios are all equal and nonzero (null in `metrics.json`).
+    pub normalized: f64,
+    /// Two-sided p-value of the ratio's sign.
+    pub p_value: f64,
+    /// `first` or `second`, or `None` when the test is not significant.
+    pub favored: Option<&'static str>,
+}
+
+/// Fits of the degree tail `x ≥ x_min`, all three models on the same tail.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DegreeFits {
+    /// Lower bound minimizing the power law's KS distance.
+    pub x_min: usize,
+    /// Nodes with degree at least `x_min`.
+    pub tail: usize,
+    /// Power-law fit.
+    pub power_law: PowerLaw,
+    /// Exponential fit.
+    pub exponential: Exponential,
+    /// Log-normal fit.
+    pub log_normal: LogNormal,
+    /// Power law vs exponential, power law vs log-normal, log-normal vs exponential.
+    pub comparisons: Vec<Comparison>,
+}
+
+/// Degrees `x ≥ x_min` as (degree, count), ascending.
+struct Tail<'a> {
+    x_min: usize,
+    values: &'a [(usize, usize)],
+    n: f64,
+}
+
+impl Tail<'_> {
+    fn weighted_sum(&self, f: impl Fn(usize) -> f64) -> f64 {
+        self.values.iter().map(|&(x, c)| c as f64 * f(x)).sum()
+    }
+
+    /// KS distance to the CDF of `log_pmf`, checked at every integer in range.
+    fn ks(&self, log_pmf: impl Fn(usize) -> f64) -> f64 {
+        let x_max = self.values.last().map_or(self.x_min, |&(x, _)| x);
+        let (mut model, mut seen, mut distance) = (0.0, 0.0, 0.0f64);
+        let mut values = self.values.iter().peekable();
+        for x in self.x_min..=x_max {
+            model += log_pmf(x).exp();
+            if let Some(&(_, c)) = values.next_if(|&&(v, _)| v == x) {
+                seen += c as f64;
+            }
+            distance = distance.max((seen / self.n - model).abs());
+        }
+        distance
+    }
+}
+
+/// Fits discrete power-law, exponential and log-normal models to the upper
+/// tail of a degree distribution given as (degree, count) pairs, following
+/// Clauset, Shalizi and Newman (2009).
+///
+/// Each model is fitted by maximum likelihood. `x_min` is the degree whose
+/// power-law fit has the smallest KS distance among those leaving at least
+/// `min_tail` nodes and two distinct degrees above it; degree 0 is never
+/// included. `None` when no degree qualifies.
+pub fn fit_degrees(distribution: &[(usize, usize)], min_tail: usize) -> Option<DegreeFits> {
+    let mut positive: Vec<(usize, usize)> =
+        distribution.iter().copied().filter(|&(x, c)| x > 0 && c > 0).collect();
+    positive.sort_unstable();
+
+    let mut best: Option<(Tail, PowerLaw)> = None;
+    let mut remaining: usize = positive.iter().map(|&(_, c)| c).sum();
+    for start in 0..positive.len().saturating_sub(1) {
+        let values = &positive[start..];
+        let tail = Tail { x_min: values[0].0, values, n: remaining as f64 };
+        remaining -= positive[start].1;
+        if (tail.n as usize) < min_tail.max(1) {
+            break;
+        }
+        let fit = fit_power_law(&tail);
+        if best.as_ref().is_none_or(|(_, b)| fit.ks < b.ks) {
+            best = Some((tail, fit));
+        }
+    }
+    let (tail, power_law) = best?;
+    let exponential = fit_exponential(&tail);
+    let log_normal = fit_log_normal(&tail);
+
+    let zeta = hurwitz_zeta(power_law.alpha, tail.x_min as f64);
+    let power_pmf = |x: usize| -power_law.alpha * (x as f64).ln() - zeta.ln();
+    let exp_pmf = |x: usize| exponential_log_pmf(exponential.lambda, tail.x_min, x);
+    let log_normal_pmf =
+        |x: usize| log_normal_log_pmf(log_normal.mu, log_normal.sigma, tail.x_min, x);
+    let comparisons = vec![
+        compare(&tail, ("power-law", &power_pmf), ("exponential", &exp_pmf)),
+        compare(&tail, ("power-law", &power_pmf), ("log-normal", &log_normal_pmf)),
+        compare(&tail, ("log-normal", &log_normal_pmf), ("exponential", &exp_pmf)),
+    ];
+
+    Some(DegreeFits {
+        x_min: tail.x_min,
+        tail: tail.n as usize,
+        power_law,
+        exponential,
+        log_normal,
+        comparisons,
+    })
+}
+
+/// Maximizes `-n ln ζ(α, x_min) - α Σ ln x`, which is concave in α.
+fn fit_power_law(tail: &Tail) -> PowerLaw {
+    let log_sum = tail.weighted_sum(|x| (x as f64).ln());
+    let log_likelihood =
+        |alpha: f64| -tail.n * hurwitz_zeta(alpha, tail.x_min as f64).ln() - alpha * log_sum;
+    let alpha = golden_section(|a| -log_likelihood(a), ALPHA_RANGE.0, ALPHA_RANGE.1);
+    let zeta = hurwitz_zeta(alpha, tail.x_min as f64);
+    PowerLaw {
+        alpha,
+        log_likelihood: log_likelihood(alpha),
+        ks: tail.ks(|x| -alpha * (x as f64).ln() - zeta.ln()),
+    }
+}
+
+/// Closed form: `λ = ln(1 + 1 / mean(x - x_min))`.
+fn fit_exponential(tail: &Tail) -> Exponential {
+    let mean = tail.weighted_sum(|x| (x - tail.x_min) as f64) / tail.n;
+    let lambda = (1.0 / mean).ln_1p();
+    let log_pmf = |x: usize| exponential_log_pmf(lambda, tail.x_min, x);
+    Exponential { lambda, log_likelihood: tail.weighted_sum(log_pmf), ks: tail.ks(log_pmf) }
+}
+
+fn exponential_log_pmf(lambda: f64, x_min: usize, x: usize) -> f64 {
+    (-(-lambda).exp_m1()).ln() - lambda * (x - x_min) as f64
+}
+
+/// Nelder–Mead over (μ, ln σ), started from the moments of `ln x`.
+fn fit_log_normal(tail: &Tail) -> LogNormal {
+    let mean = tail.weighted_sum(|x| (x as f64).ln()) / tail.n;
+    let variance = tail.weighted_sum(|x| ((x as f64).ln() - mean).powi(2)) / tail.n;
+    let start = [mean, variance.sqrt().max(0.1).ln()];
+    let negative = |p: [f64; 2]| {
+        let ll = tail.weighted_sum(|x| log_normal_log_pmf(p[0], p[1].exp(), tail.x_min, x));
+        if ll.is_finite() {
+            -ll
+        } else {
+            f64::INFINITY
+        }
+    };
+    let [mu, log_sigma] = nelder_mead(negative, start);
+    let sigma = log_sigma.exp();
+    let log_pmf = |x: usize| log_normal_log_pmf(mu, sigma, tail.x_min, x);
+    LogNormal { mu, sigma, log_likelihood: tail.weighted_sum(log_pmf), ks: tail.ks(log_pmf) }
+}
+
+fn log_normal_log_pmf(mu: f64, sigma: f64, x_min: usize, x: usize) -> f64 {
+    let z = |v: usize| ((v as f64).ln() - mu) / sigma;
+    let (lo, hi) = (z(x), z(x + 1));
+    // Take the difference on whichever side of the mean keeps precision.
+    let mass = if lo >= 0.0 {
+        upper_normal(lo) - upper_normal(hi)
+    } else {
+        upper_normal(-hi) - upper_normal(-lo)
+    };
+    mass.ln() - upper_normal(z(x_min)).ln()
+}
+
+fn compare(
+    tail: &Tail,
+    (first, p): (&'static str, &dyn Fn(usize) -> f64),
+    (second, q): (&'static str, &dyn Fn(usize) -> f64),
+) -> Comparison {
+    let log_ratio = tail.weighted_sum(|x| p(x) - q(x));
+    let mean = log_ratio / tail.n;
+    let deviation = (tail.weighted_sum(|x| (p(x) - q(x) - mean).powi(2)) / tail.n).sqrt();
+    let (normalized, p_value) = if deviation > 0.0 {
+        let normalized = log_ratio / (tail.n.sqrt() * deviation);
+        (normalized, erfc(normalized.abs() / SQRT_2))
+    } else if log_ratio == 0.0 {
+        (0.0, 1.0)
+    } else {
+        (log_ratio.signum() * f64::INFINITY, 0.0)
+    };
+    let favored = match p_value < SIGNIFICANCE {
+        true if log_ratio > 0.0 => Some(first),
+        true if log_ratio < 0.0 => Some(second),
+        _ => None,
+    };
+    Comparison { first, second, log_ratio, normalized, p_value, favored }
+}
+
+/// Hurwitz zeta `ζ(s, q) = Σ_{k≥0} (q + k)^-s` for `s > 1`, `q > 0`: ten
+/// terms summed directly, the rest by Euler–Maclaurin.
+fn hurwitz_zeta(s: f64, q: f64) -> f64 {
+    // B_2j / (2j)! for j = 1..6.
+    const BERNOULLI: [f64; 6] = [
+        1.0 / 12.0,
+        -1.0 / 720.0,
+        1.0 / 30240.0,
+        -1.0 / 1209600.0,
+        1.0 / 47900160.0,
+        -691.0 / 1307674368000.0,
+    ];
+    const DIRECT: usize = 10;
+    let head: f64 = (0..DIRECT).map(|k| (q + k as f64).powf(-s)).sum();
+    let a = q + DIRECT as f64;
+    let mut tail = a.powf(1.0 - s) / (s - 1.0) + a.powf(-s) / 2.0;
+    // Rising factorial s (s + 1) ... (s + 2j - 2) times a^(-s - 2j + 1).
+    let mut term = s * a.powf(-s - 1.0);
+    for (j, b) in BERNOULLI.iter().enumerate() {
+        tail += b * term;
+        let k = 2.0 * j as f64;
+        term *= (s + k + 1.0) * (s + k + 2.0) / (a * a);
+    }
+    head + tail
+}
+
+/// `P(Z > z)` for a standard normal `Z`.
+fn upper_normal(z: f64) -> f64 {
+    0.5 * erfc(z / SQRT_2)
+}
+
+/// Complementary error function, relative error below 1.2e-7 everywhere
+/// (Numerical Recipes' Chebyshev fit).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87
+                                    + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let r = t * poly.exp();
+    if x >= 0.0 {
+        r
+    } else {
+        2.0 - r
+    }
+}
+
+/// Minimizes a unimodal `f` on `[lo, hi]`.
+fn golden_section(f: impl Fn(f64) -> f64, mut lo: f64, mut hi: f64) -> f64 {
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let mut a = hi - ratio * (hi - lo);
+    let mut b = lo + ratio * (hi - lo);
+    let (mut fa, mut fb) = (f(a), f(b));
+    while hi - lo > 1e-9 {
+        if fa < fb {
+            hi = b;
+            (b, fb) = (a, fa);
+            a = hi - ratio * (hi - lo);
+            fa = f(a);
+        } else {
+            lo = a;
+            (a, fa) = (b, fb);
+            b = lo + ratio * (hi - lo);
+            fb = f(b);
+        }
+    }
+    (lo + hi) / 2.0
+}
+
+/// Minimizes `f` over the plane from `start` with the standard
+/// reflection/expansion/contraction/shrink coefficients (1, 2, 1/2, 1/2).
+fn nelder_mead(f: impl Fn([f64; 2]) -> f64, start: [f64; 2]) -> [f64; 2] {
+    const MAX_ITERATIONS: usize = 5000;
+    let at = |p: [f64; 2]| (p, f(p));
+    let mut simplex = [
+        at(start),
+        at([start[0] + 0.5, start[1]]),
+        at([start[0], start[1] + 0.5]),
+    ];
+    let lerp =
+        |a: [f64; 2], b: [f64; 2], t: f64| [a[0] + t * (b[0] - a[0]), a[1] + t * (b[1] - a[1])];
+    for _ in 0..MAX_ITERATIONS {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (best, worst) = (simplex[0].1, simplex[2].1);
+        if worst - best <= 1e-12 * (best.abs() + 1e-12) {
+            break;
+        }
+        let centroid = lerp(simplex[0].0, simplex[1].0, 0.5);
+        let reflected = at(lerp(simplex[2].0, centroid, 2.0));
+        if reflected.1 < best {
+            let expanded = at(lerp(simplex[2].0, centroid, 3.0));
+            simplex[2] = if expanded.1 < reflected.1 { expanded } else { reflected };
+        } else if reflected.1 < simplex[1].1 {
+            simplex[2] = reflected;
+        } else {
+            let contracted = at(lerp(simplex[2].0, centroid, 0.5));
+            if contracted.1 < worst {
+                simplex[2] = contracted;
+            } else {
+                for i in 1..3 {
+                    simplex[i] = at(lerp(simplex[0].0, simplex[i].0, 0.5));
+                }
+            }
+        }
+    }
+    simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+    simplex[0].0
+}
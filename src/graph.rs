@@ -1,38 +1,94 @@
 // src/graph.rs
 
-use std::collections::{HashMap, VecDeque};
-use chrono::NaiveDate;
+//! Reading the (DAY, AREA_NAME) CSV and building the (bucket, area) and area graphs.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use csv::ReaderBuilder;
 use petgraph::graph::{UnGraph, NodeIndex};
 use itertools::Itertools;
+use crate::bfs::BfsScratch;
+use crate::error::GraphError;
+use crate::period::{parse_time_occ, Period, TimeBucket};
+use crate::quality::{normalize_area, DataQuality, ParseMode, SkipReason};
+
+/// One node per unique (time bucket, area); with the default day bucket the
+/// bucket is a calendar day.
+pub type Graph = UnGraph<(Period, String), ()>;
+
+/// Area-level projection: one node per AREA_NAME, edge weight = number of
+/// buckets (days by default) in which both areas appear.
+pub type AreaGraph = UnGraph<String, usize>;
 
-pub type Graph = UnGraph<(NaiveDate, String), ()>;
+/// Header names of the input columns, resolved when the CSV is opened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnMap {
+    /// Date (or date-time) column.
+    pub day: String,
+    /// Area name column.
+    pub area: String,
+    /// Time of day, only read for hourly buckets.
+    pub time: String,
+}
+
+impl Default for ColumnMap {
+    fn default() -> Self {
+        ColumnMap {
+            day: "DAY".to_string(),
+            area: "AREA_NAME".to_string(),
+            time: "TIME_OCC".to_string(),
+        }
+    }
+}
+
+/// Options controlling how the input is read and which edges `build_graph_with` adds.
+#[derive(Debug, Clone, Default)]
+pub struct GraphOptions {
+    /// Link `(d, area)` to `(d + k, area)` for every `1 <= k <= temporal_lag`,
+    /// where `k` counts buckets. `0` keeps only same-bucket edges.
+    pub temporal_lag: usize,
+    /// Granularity of the time component of each node.
+    pub bucket: TimeBucket,
+    /// Which headers hold the day, area and time columns.
+    pub columns: ColumnMap,
+    /// Whether bad rows abort the read or are skipped.
+    pub mode: ParseMode,
+}
 
 /// Build a graph where each unique (day, area) is a node,
 /// and nodes are connected if they occur on the same day.
-pub fn build_graph(path: &str) -> Result<Graph, Box<dyn std::error::Error>> {
-    // 1) Read all (DAY, AREA_NAME) pairs
-    let mut rdr = ReaderBuilder::new().has_headers(true).from_path(path)?;
-    let mut entries = Vec::new();
-    for result in rdr.records() {
-        let record = result?;
-        let day = NaiveDate::parse_from_str(&record[0], "%Y-%m-%d")?;
-        let area = record[1].to_string();
-        entries.push((day, area));
-    }
+pub fn build_graph<P: AsRef<Path>>(path: P) -> Result<Graph, GraphError> {
+    build_graph_with(path, &GraphOptions::default())
+}
 
-    // 2) Create graph & index map
+/// Like `build_graph`, but groups by `options.bucket` and adds temporal edges
+/// according to `options.temporal_lag`.
+pub fn build_graph_with<P: AsRef<Path>>(
+    path: P,
+    options: &GraphOptions,
+) -> Result<Graph, GraphError> {
+    let entries = read_entries(path, options)?;
+    Ok(graph_from_entries(&entries, options))
+}
+
+/// Build the (bucket, area) graph from already-read entries; only
+/// `options.temporal_lag` is used.
+pub fn graph_from_entries(entries: &[(Period, String)], options: &GraphOptions) -> Graph {
+    // 1) Create graph & index map
     let mut graph = Graph::new_undirected();
-    let mut idx_map: HashMap<(NaiveDate, String), NodeIndex> = HashMap::new();
-    for (day, area) in &entries {
-        idx_map.entry((day.clone(), area.clone()))
-            .or_insert_with(|| graph.add_node((day.clone(), area.clone())));
+    let mut idx_map: HashMap<(Period, String), NodeIndex> = HashMap::new();
+    for (period, area) in entries {
+        idx_map.entry((*period, area.clone()))
+            .or_insert_with(|| graph.add_node((*period, area.clone())));
     }
 
-    // 3) Group by day and fully connect each day's nodes
-    let mut daily: HashMap<NaiveDate, Vec<NodeIndex>> = HashMap::new();
-    for ((day, _), &idx) in &idx_map {
-        daily.entry(day.clone()).or_default().push(idx);
+    // 2) Group by bucket and fully connect each bucket's nodes; buckets and
+    //    nodes go in a fixed order so edge order, and every float sum over
+    //    neighbours, is the same from run to run
+    let mut daily: BTreeMap<Period, Vec<NodeIndex>> = BTreeMap::new();
+    for idx in graph.node_indices() {
+        daily.entry(graph[idx].0).or_default().push(idx);
     }
     for nodes in daily.values() {
         for (a, b) in nodes.iter().tuple_combinations() {
@@ -42,29 +98,322 @@ pub fn build_graph(path: &str) -> Result<Graph, Box<dyn std::error::Error>> {
         }
     }
 
-    Ok(graph)
+    // 3) Link each area to itself in the following `temporal_lag` buckets
+    for idx in graph.node_indices() {
+        let (period, area) = graph[idx].clone();
+        for lag in 1..=options.temporal_lag {
+            let later = period.offset(lag as u32);
+            if let Some(&other) = idx_map.get(&(later, area.clone())) {
+                graph.add_edge(idx, other, ());
+            }
+        }
+    }
+
+    graph
 }
 
-/// Perform a BFS from `start` and return a map of distances to every reachable node.
-pub fn bfs_distances(
-    graph: &Graph,
-    start: NodeIndex,
-) -> HashMap<NodeIndex, usize> {
-    let mut dist: HashMap<NodeIndex, usize> = HashMap::new();
-    let mut queue: VecDeque<NodeIndex> = VecDeque::new();
+/// Build the weighted area co-occurrence graph directly from a (DAY, AREA_NAME) CSV.
+pub fn build_area_graph<P: AsRef<Path>>(
+    path: P,
+) -> Result<AreaGraph, GraphError> {
+    build_area_graph_with(path, &GraphOptions::default())
+}
+
+/// Like `build_area_graph`, but reads the file with `options.columns` and
+/// `options.mode` and counts co-occurrence per `options.bucket`.
+///
+/// Temporal edges only join an area to itself, so they have no area-level
+/// counterpart; a nonzero `options.temporal_lag` is rejected.
+pub fn build_area_graph_with<P: AsRef<Path>>(
+    path: P,
+    options: &GraphOptions,
+) -> Result<AreaGraph, GraphError> {
+    let path = path.as_ref();
+    if options.temporal_lag > 0 {
+        return Err(GraphError::UnsupportedOption {
+            path: path.to_path_buf(),
+            option: "temporal_lag".to_string(),
+            reason: "the area graph has no temporal edges".to_string(),
+        });
+    }
+    let entries = read_entries(path, options)?;
+    Ok(project_areas(entries.iter().map(|(day, area)| (*day, area.as_str()))))
+}
+
+/// Project the (day, area) nodes of `graph` onto areas, ignoring its edges.
+pub fn area_projection<G: Topology<Node = (Period, String)>>(graph: &G) -> AreaGraph {
+    project_areas(graph.nodes().map(|node| {
+        let (period, area) = graph.node(node);
+        (*period, area.as_str())
+    }))
+}
+
+/// Count, for every pair of areas, the buckets in which both appear.
+/// Nodes are added in area-name order so indices are stable across runs.
+pub fn project_areas<'a, I>(pairs: I) -> AreaGraph
+where
+    I: IntoIterator<Item = (Period, &'a str)>,
+{
+    let mut daily: BTreeMap<Period, BTreeSet<&str>> = BTreeMap::new();
+    let mut areas: BTreeSet<&str> = BTreeSet::new();
+    for (day, area) in pairs {
+        daily.entry(day).or_default().insert(area);
+        areas.insert(area);
+    }
+
+    let mut graph = AreaGraph::new_undirected();
+    let idx_map: HashMap<&str, NodeIndex> = areas
+        .into_iter()
+        .map(|area| (area, graph.add_node(area.to_string())))
+        .collect();
+
+    let mut weights: BTreeMap<(NodeIndex, NodeIndex), usize> = BTreeMap::new();
+    for day_areas in daily.values() {
+        for (a, b) in day_areas.iter().tuple_combinations() {
+            *weights.entry((idx_map[a], idx_map[b])).or_default() += 1;
+        }
+    }
+    for ((a, b), days) in weights {
+        graph.add_edge(a, b, days);
+    }
+
+    graph
+}
+
+/// Read every (day, area) row of `path` in file order, mapping each day to
+/// `options.bucket`. Columns are looked up by header name via `options.columns`.
+///
+/// Hourly buckets take the time of day from the time column, or, when the file
+/// has none, from a date cell written as `YYYY-MM-DD HH:MM[:SS]`; date-only
+/// data without a time column is rejected with `GraphError::MissingTime`.
+pub fn read_entries<P: AsRef<Path>>(
+    path: P,
+    options: &GraphOptions,
+) -> Result<Vec<(Period, String)>, GraphError> {
+    read_entries_checked(path, options).map(|(entries, _)| entries)
+}
+
+/// Like `read_entries`, but also returns a data-quality summary of the file.
+///
+/// In `ParseMode::Lenient`, unparseable rows are skipped and counted instead of
+/// aborting the read, and area names are normalized with `normalize_area`.
+pub fn read_entries_checked<P: AsRef<Path>>(
+    path: P,
+    options: &GraphOptions,
+) -> Result<(Vec<(Period, String)>, DataQuality), GraphError> {
+    let path = path.as_ref();
+    let lenient = options.mode == ParseMode::Lenient;
+    let mut rdr = ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(lenient)
+        .from_path(path)
+        .map_err(|e| GraphError::csv(path, e))?;
+    let headers = rdr.headers().map_err(|e| GraphError::csv(path, e))?.clone();
+    let column = |name: &str| {
+        headers.iter().position(|h| h.trim() == name).ok_or_else(|| {
+            GraphError::MissingColumn {
+                path: path.to_path_buf(),
+                column: name.to_string(),
+                available: headers.iter().map(str::to_string).collect(),
+            }
+        })
+    };
+    let columns = &options.columns;
+    let day_col = column(&columns.day)?;
+    let area_col = column(&columns.area)?;
+    let hourly = options.bucket == TimeBucket::Hour;
+    let time_col = match hourly {
+        true => headers.iter().position(|h| h.trim() == columns.time),
+        false => None,
+    };
 
-    dist.insert(start, 0);
-    queue.push_back(start);
+    let mut quality = DataQuality::new(options.mode);
+    let mut entries = Vec::new();
+    for result in rdr.records() {
+        quality.rows_read += 1;
+        let record = match result {
+            Ok(record) => record,
+            Err(_) if lenient => {
+                quality.skip(SkipReason::MalformedRow);
+                continue;
+            }
+            Err(e) => return Err(GraphError::csv(path, e)),
+        };
+        let line = record.position().map_or(0, |pos| pos.line());
+        let field = |col: usize| record.get(col).unwrap_or("");
 
-    while let Some(node) = queue.pop_front() {
-        let d = dist[&node];
-        for nbr in graph.neighbors(node) {
-            if !dist.contains_key(&nbr) {
-                dist.insert(nbr, d + 1);
-                queue.push_back(nbr);
+        let (day, day_time) = match parse_day(field(day_col)) {
+            Ok(day) => day,
+            Err(_) if lenient => {
+                quality.skip(SkipReason::InvalidDate);
+                continue;
+            }
+            Err(source) => {
+                return Err(GraphError::DateParse {
+                    path: path.to_path_buf(),
+                    line,
+                    column: columns.day.clone(),
+                    value: field(day_col).to_string(),
+                    source,
+                })
+            }
+        };
+        let time = match time_col {
+            None if hourly => match day_time {
+                Some(time) => time,
+                None => {
+                    return Err(GraphError::MissingTime {
+                        path: path.to_path_buf(),
+                        line,
+                        column: columns.day.clone(),
+                        value: field(day_col).to_string(),
+                        time_column: columns.time.clone(),
+                    })
+                }
+            },
+            None => NaiveTime::MIN,
+            Some(col) => match parse_time_occ(field(col)) {
+                Some(time) => time,
+                None if lenient => {
+                    quality.skip(SkipReason::InvalidTime);
+                    continue;
+                }
+                None => {
+                    return Err(GraphError::TimeParse {
+                        path: path.to_path_buf(),
+                        line,
+                        column: columns.time.clone(),
+                        value: field(col).to_string(),
+                    })
+                }
+            },
+        };
+        let area = if lenient {
+            let area = normalize_area(field(area_col));
+            if area.is_empty() {
+                quality.skip(SkipReason::MissingArea);
+                continue;
+            }
+            if area != field(area_col) {
+                quality.normalized_areas += 1;
             }
+            area
+        } else {
+            field(area_col).to_string()
+        };
+
+        quality.keep(day, &area);
+        entries.push((options.bucket.period(day, time), area));
+    }
+    quality.finish();
+
+    if entries.is_empty() && quality.rows_read > 0 {
+        return Err(GraphError::AllRowsRejected {
+            path: path.to_path_buf(),
+            quality: Box::new(quality),
+        });
+    }
+    if entries.is_empty() {
+        return Err(GraphError::EmptyInput { path: path.to_path_buf() });
+    }
+    Ok((entries, quality))
+}
+
+/// Parse a `YYYY-MM-DD` date cell, optionally followed by a time of day
+/// (`HH:MM` or `HH:MM:SS`, after a space or `T`).
+fn parse_day(value: &str) -> Result<(NaiveDate, Option<NaiveTime>), chrono::ParseError> {
+    let value = value.trim();
+    match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Ok(day) => Ok((day, None)),
+        Err(err) => ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+            .map(|at| (at.date(), Some(at.time())))
+            .ok_or(err),
+    }
+}
+
+/// Read-only view of an undirected graph, implemented both by petgraph graphs
+/// and by `CliqueGraph`, so the analysis functions work on either.
+///
+/// Node indices are dense: every index in `0..node_count()` is a node.
+pub trait Topology {
+    /// Label attached to each node.
+    type Node;
+
+    /// Number of nodes.
+    fn node_count(&self) -> usize;
+
+    /// Number of undirected edges.
+    fn edge_count(&self) -> usize;
+
+    /// Label of `node`.
+    fn node(&self, node: NodeIndex) -> &Self::Node;
+
+    /// Number of neighbours of `node`.
+    fn degree(&self, node: NodeIndex) -> usize;
+
+    /// Calls `f` once per neighbour of `node`.
+    fn for_each_neighbor<F: FnMut(NodeIndex)>(&self, node: NodeIndex, f: F);
+
+    /// All node indices in order.
+    fn nodes(&self) -> impl Iterator<Item = NodeIndex> {
+        (0..self.node_count()).map(NodeIndex::new)
+    }
+
+    /// BFS from `start` using the buffers in `scratch`, calling `visit(node, distance)`
+    /// once per reachable node in nondecreasing distance order (`start` first, at 0).
+    fn bfs_with<F: FnMut(NodeIndex, usize)>(
+        &self,
+        start: NodeIndex,
+        scratch: &mut BfsScratch,
+        mut visit: F,
+    ) {
+        scratch.begin(self.node_count(), start);
+        while let Some((node, d)) = scratch.pop() {
+            visit(node, d);
+            self.for_each_neighbor(node, |nbr| scratch.discover(nbr, d + 1));
         }
     }
 
+    /// Like `bfs_with`, with freshly allocated buffers.
+    fn bfs_visit<F: FnMut(NodeIndex, usize)>(&self, start: NodeIndex, visit: F) {
+        self.bfs_with(start, &mut BfsScratch::default(), visit);
+    }
+}
+
+impl<N, E> Topology for UnGraph<N, E> {
+    type Node = N;
+
+    fn node_count(&self) -> usize {
+        UnGraph::node_count(self)
+    }
+
+    fn edge_count(&self) -> usize {
+        UnGraph::edge_count(self)
+    }
+
+    fn node(&self, node: NodeIndex) -> &N {
+        &self[node]
+    }
+
+    fn degree(&self, node: NodeIndex) -> usize {
+        self.neighbors(node).count()
+    }
+
+    fn for_each_neighbor<F: FnMut(NodeIndex)>(&self, node: NodeIndex, f: F) {
+        self.neighbors(node).for_each(f);
+    }
+}
+
+/// Perform a BFS from `start` and return a map of distances to every reachable node.
+pub fn bfs_distances<G: Topology>(
+    graph: &G,
+    start: NodeIndex,
+) -> HashMap<NodeIndex, usize> {
+    let mut dist: HashMap<NodeIndex, usize> = HashMap::new();
+    graph.bfs_visit(start, |node, d| {
+        dist.insert(node, d);
+    });
     dist
 }
@@ -0,0 +1,131 @@
+// src/ingest.rs
+
+//! Cleans a raw LAPD export into the (DAY, AREA_NAME) CSV.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use csv::{ReaderBuilder, Writer};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Columns a raw row must have (non-empty) to be kept, after header normalization.
+pub const REQUIRED_COLUMNS: [&str; 6] = ["DR_NO", "DATE_OCC", "AREA_NAME", "LAT", "LON", "Crm_Cd_Desc"];
+
+/// Optional column carried into the cleaned output when present, for hourly buckets.
+pub const OPTIONAL_COLUMNS: [&str; 1] = ["TIME_OCC"];
+
+/// Row counts at each stage of `ingest_raw`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IngestSummary {
+    /// Data rows in the raw export.
+    pub raw_rows: usize,
+    /// Rows left after dropping incomplete or unparseable ones.
+    pub clean_rows: usize,
+    /// Unique (DAY, AREA_NAME) pairs written to the day/area file.
+    pub unique_pairs: usize,
+}
+
+/// Normalize a raw header: trim it and replace spaces with underscores
+/// (`"DATE OCC"` → `"DATE_OCC"`).
+pub(crate) fn normalize_header(header: &str) -> String {
+    header.trim().replace(' ', "_")
+}
+
+/// Parse a raw `DATE_OCC` value. The LAPD export uses `MM/DD/YYYY hh:mm:ss AM`;
+/// ISO dates (with or without a time) are accepted as well.
+pub(crate) fn parse_date_occ(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    ["%m/%d/%Y %I:%M:%S %p", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(value, fmt).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+}
+
+/// Clean a raw LAPD crime export and reduce it to unique (DAY, AREA_NAME) pairs.
+///
+/// Mirrors `scripts/1_download_and_clean.py` and `scripts/0_preprocess.py`:
+/// normalizes column names, parses `DATE_OCC`, drops rows missing any of
+/// `REQUIRED_COLUMNS`, then writes the first occurrence of each
+/// (DAY, AREA_NAME) to `day_area_out`. If `clean_out` is given, the cleaned
+/// rows (required columns plus `TIME_OCC` when present, `DATE_OCC` as
+/// `YYYY-MM-DD`) are written there too.
+pub fn ingest_raw<P: AsRef<Path>, Q: AsRef<Path>>(
+    raw: P,
+    day_area_out: Q,
+    clean_out: Option<&Path>,
+) -> Result<IngestSummary, Box<dyn std::error::Error>> {
+    let mut rdr = ReaderBuilder::new().has_headers(true).flexible(true).from_path(raw)?;
+    let headers: Vec<String> = rdr.headers()?.iter().map(normalize_header).collect();
+    let position = |name: &str| headers.iter().position(|h| h == name);
+
+    let mut required = Vec::new();
+    for name in REQUIRED_COLUMNS {
+        let col = position(name).ok_or_else(|| {
+            format!("raw CSV has no {} column (found: {})", name, headers.join(", "))
+        })?;
+        required.push(col);
+    }
+    let optional: Vec<(&str, usize)> = OPTIONAL_COLUMNS
+        .iter()
+        .filter_map(|&name| position(name).map(|col| (name, col)))
+        .collect();
+    let (date_col, area_col) = (required[1], required[2]);
+
+    let mut clean_wtr = match clean_out {
+        Some(path) => {
+            let mut wtr = Writer::from_path(path)?;
+            let header: Vec<&str> = REQUIRED_COLUMNS
+                .iter()
+                .copied()
+                .chain(optional.iter().map(|&(name, _)| name))
+                .collect();
+            wtr.write_record(&header)?;
+            Some(wtr)
+        }
+        None => None,
+    };
+    let mut pair_wtr = Writer::from_path(day_area_out)?;
+    pair_wtr.write_record(["DAY", "AREA_NAME"])?;
+
+    let mut summary = IngestSummary::default();
+    let mut seen: HashSet<(NaiveDate, String)> = HashSet::new();
+    for result in rdr.records() {
+        let record = result?;
+        summary.raw_rows += 1;
+
+        let field = |col: usize| record.get(col).map(str::trim).unwrap_or("");
+        if required.iter().any(|&col| field(col).is_empty()) {
+            continue;
+        }
+        let Some(occurred) = parse_date_occ(field(date_col)) else {
+            continue;
+        };
+        summary.clean_rows += 1;
+
+        let day = occurred.date();
+        if let Some(wtr) = clean_wtr.as_mut() {
+            let day_str = day.format("%Y-%m-%d").to_string();
+            let row: Vec<&str> = required
+                .iter()
+                .map(|&col| if col == date_col { day_str.as_str() } else { field(col) })
+                .chain(optional.iter().map(|&(_, col)| field(col)))
+                .collect();
+            wtr.write_record(&row)?;
+        }
+
+        let area = field(area_col).to_string();
+        if seen.insert((day, area.clone())) {
+            pair_wtr.write_record(&[day.format("%Y-%m-%d").to_string(), area])?;
+            summary.unique_pairs += 1;
+        }
+    }
+
+    if let Some(mut wtr) = clean_wtr {
+        wtr.flush()?;
+    }
+    pair_wtr.flush()?;
+    Ok(summary)
+}
@@ -0,0 +1,1286 @@
+//! LA crime-graph analysis library.
+//!
+//! - [`bfs`]: the dense, buffer-reusing BFS kernel behind all path-based metrics
+//! - [`error`]: the [`GraphError`] type returned when an input CSV cannot be read
+//! - [`ingest`]: cleans a raw LAPD export into the (DAY, AREA_NAME) CSV, replacing the
+//!   Python preprocessing scripts
+//! - [`period`]: hour/day/week/month time buckets used as the time part of every node
+//! - [`quality`]: strict/lenient parsing and the data-quality report of an input file
+//! - [`graph`]: builds the (DAY, AREA_NAME) graph from a CSV, optionally with temporal edges,
+//!   the weighted area co-occurrence projection, and runs BFS over it
+//! - [`analysis`]: functions generic over [`Topology`]: degree distribution and assortativity,
+//!   average path length, closeness, components and their eccentricity extremes, k-cores,
+//!   clustering and triangles; area co-occurrence rankings and PageRank, eigenvector and Katz
+//!   centrality of the area graph
+//! - [`all_pairs`](mod@all_pairs): one parallel, deterministic BFS sweep feeding path length,
+//!   closeness, eccentricity and the distance histogram, or a seeded pivot sample estimating
+//!   the first two with confidence intervals
+//! - [`betweenness`](mod@betweenness): exact and sampled Brandes betweenness of nodes and edges
+//! - [`community`]: Louvain community detection and modularity on weighted graphs, and seeded
+//!   label propagation for the full (bucket, area) graph
+//! - [`fit`]: maximum-likelihood power-law, exponential and log-normal fits of the degree
+//!   tail, with KS-selected `x_min` and likelihood-ratio comparisons
+//! - [`clique`]: the same graph with each bucket's clique stored implicitly, for large inputs
+//! - [`bipartite`]: the linear-size day–area bipartite model, its projections and clustering
+//! - [`report`]: runs a selection of metrics and exports `metrics.json` plus CSV tables
+//!
+//! ```no_run
+//! use final_project::{build_graph, Metric, Report};
+//!
+//! let graph = build_graph("data/day_area.csv")?;
+//! let report = Report::compute(&graph, &[Metric::Degree, Metric::Components], 5);
+//! report.write("report")?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#![warn(missing_docs)]
+
+pub mod bfs;
+pub mod error;
+pub mod ingest;
+pub mod period;
+pub mod quality;
+pub mod graph;
+pub mod analysis;
+pub mod all_pairs;
+pub mod betweenness;
+pub mod community;
+pub mod fit;
+pub mod bipartite;
+pub mod clique;
+pub mod report;
+
+pub use crate::graph::{
+    build_graph,
+    build_graph_with,
+    build_area_graph,
+    build_area_graph_with,
+    graph_from_entries,
+    read_entries,
+    read_entries_checked,
+    area_projection,
+    project_areas,
+    bfs_distances,
+    AreaGraph,
+    ColumnMap,
+    Graph,
+    GraphOptions,
+    Topology,
+};
+pub use crate::analysis::{
+    degree_distribution,
+    degree_assortativity,
+    avg_neighbor_degree,
+    avg_shortest_path,
+    closeness_centrality,
+    closeness_centrality_with,
+    top_scores,
+    Closeness,
+    pagerank,
+    eigenvector_centrality,
+    katz_centrality,
+    largest_eigenvalue,
+    Centrality,
+    Iteration,
+    clustering,
+    Clustering,
+    core_decomposition,
+    CoreDecomposition,
+    component_membership,
+    component_spans,
+    component_shapes,
+    ComponentShape,
+    ComponentSpan,
+    component_count,
+    area_strengths,
+    top_cooccurrences,
+};
+pub use crate::bfs::{BfsEngine, BfsScratch, BfsSummary};
+pub use crate::error::GraphError;
+pub use crate::ingest::{ingest_raw, IngestSummary, OPTIONAL_COLUMNS, REQUIRED_COLUMNS};
+pub use crate::period::{parse_time_occ, Period, TimeBucket};
+pub use crate::quality::{normalize_area, DataQuality, ParseMode, SkipReason, KNOWN_AREAS};
+pub use crate::betweenness::{
+    betweenness, sampled_betweenness, top_edges, Betweenness, EdgeKey, EdgeScore,
+};
+pub use crate::community::{
+    label_propagation, louvain, modularity, normalized_mutual_information, topology_modularity,
+    EdgeWeight, LabelPropagation, Partition,
+};
+pub use crate::fit::{
+    fit_degrees, Comparison, DegreeFits, Exponential, LogNormal, PowerLaw, SIGNIFICANCE,
+};
+pub use crate::clique::{build_clique_graph, CliqueGraph};
+pub use crate::bipartite::{
+    average_bipartite_clustering, bipartite_clustering, bipartite_degrees, bipartite_from_pairs,
+    bipartite_projection, build_bipartite_graph, project_area_graph, project_days,
+    BipartiteGraph, BipartiteNode, BipartiteSummary, DayGraph,
+};
+pub use crate::all_pairs::{
+    all_pairs, sampled_pairs, AllPairs, Estimate, PathMode, PivotSums, SampledPairs,
+};
+pub use crate::report::{
+    AreaCentrality, AreaCommunities, CentralityOptions, ClosenessEstimate, ClusteringSummary,
+    Community, ComponentRow, ComponentShapeRow, ComponentSummary, LargestShape, Metric,
+    NodeClustering, NodeComponent, NodeCore, NodeEccentricity, PathSample, PropagationOptions,
+    PropagationSummary, RankedCentrality, Report, ReportOptions, ShapeSummary,
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{
+        build_graph, build_graph_with, build_area_graph, build_area_graph_with, bfs_distances,
+        graph_from_entries,
+        read_entries, read_entries_checked, AreaGraph, ColumnMap, Graph, GraphOptions, Topology,
+    };
+    use crate::analysis::{
+        avg_shortest_path, closeness_centrality, closeness_centrality_with, eigenvector_centrality,
+        katz_centrality, largest_eigenvalue, pagerank, clustering, core_decomposition,
+        component_membership, component_shapes, component_spans, Closeness, Iteration,
+        component_count,
+        degree_distribution, top_cooccurrences, top_scores, degree_assortativity,
+        avg_neighbor_degree,
+    };
+    use crate::all_pairs::{all_pairs, sampled_pairs, PathMode};
+    use crate::betweenness::{betweenness, sampled_betweenness, top_edges};
+    use crate::fit::fit_degrees;
+    use crate::community::{
+        label_propagation, louvain, modularity, normalized_mutual_information,
+    };
+    use crate::bipartite::{
+        bipartite_from_pairs, bipartite_clustering, build_bipartite_graph, project_area_graph,
+        project_days,
+    };
+    use crate::bfs::BfsEngine;
+    use crate::clique::CliqueGraph;
+    use crate::error::GraphError;
+    use crate::ingest::ingest_raw;
+    use crate::period::TimeBucket;
+    use crate::quality::{ParseMode, SkipReason};
+    use crate::report::{CentralityOptions, Metric, Report, ReportOptions};
+    use petgraph::graph::NodeIndex;
+    use chrono::NaiveDate;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A directory unique to one test run and call, removed when dropped, so
+    /// parallel tests never share fixture files.
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new() -> Self {
+            static NEXT: AtomicUsize = AtomicUsize::new(0);
+            let name = format!(
+                "final_project_test_{}_{}",
+                std::process::id(),
+                NEXT.fetch_add(1, Ordering::Relaxed)
+            );
+            let dir = std::env::temp_dir().join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            Scratch(dir)
+        }
+
+        fn path(&self, name: &str) -> PathBuf {
+            self.0.join(name)
+        }
+
+        /// Writes `data` to `input.csv` in the directory.
+        fn csv(&self, data: &str) -> PathBuf {
+            let path = self.path("input.csv");
+            std::fs::write(&path, data).unwrap();
+            path
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_bfs_chain() {
+        let mut g: Graph = Graph::new_undirected();
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1)
+            .expect("valid date")
+            .into();
+        let a = g.add_node((date, "A".to_string()));
+        let b = g.add_node((date, "B".to_string()));
+        let c = g.add_node((date, "C".to_string()));
+        g.add_edge(a, b, ());
+        g.add_edge(b, c, ());
+        let dist = bfs_distances(&g, a);
+        assert_eq!(dist.get(&a), Some(&0));
+        assert_eq!(dist.get(&b), Some(&1));
+        assert_eq!(dist.get(&c), Some(&2));
+    }
+
+    #[test]
+    fn test_degree_distribution_triangle() {
+        let mut g: Graph = Graph::new_undirected();
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1)
+            .expect("valid date")
+            .into();
+        let a = g.add_node((date, "A".to_string()));
+        let b = g.add_node((date, "B".to_string()));
+        let c = g.add_node((date, "C".to_string()));
+        g.add_edge(a, b, ());
+        g.add_edge(b, c, ());
+        g.add_edge(c, a, ());
+        let deg = degree_distribution(&g);
+        assert_eq!(deg.get(&2), Some(&3));
+    }
+
+    #[test]
+    fn test_component_count_isolated() {
+        let mut g: Graph = Graph::new_undirected();
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1)
+            .expect("valid date")
+            .into();
+        g.add_node((date, "X".to_string()));
+        g.add_node((date, "Y".to_string()));
+        assert_eq!(component_count(&g), 2);
+    }
+
+    #[test]
+    fn test_build_graph_tiny() {
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n\
+                    2025-04-01,B\n\
+                    2025-04-02,A\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let graph: Graph = build_graph(tmp.to_str().unwrap()).unwrap();
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 1);
+    }
+
+    #[test]
+    fn test_report_selected_metrics_only() {
+        let mut g: Graph = Graph::new_undirected();
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1)
+            .expect("valid date")
+            .into();
+        let a = g.add_node((date, "A".to_string()));
+        let b = g.add_node((date, "B".to_string()));
+        g.add_edge(a, b, ());
+        let report = Report::compute(&g, &[Metric::Components, Metric::Closeness], 1);
+        let json = report.to_json();
+        assert_eq!(json["components"], 1);
+        assert_eq!(json["top1_closeness"].as_array().map(|v| v.len()), Some(1));
+        assert!(json.get("avg_path").is_none());
+        assert!(report.degree_distribution.is_none());
+    }
+
+    #[test]
+    fn test_build_graph_temporal_lag() {
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n\
+                    2025-04-01,B\n\
+                    2025-04-02,A\n\
+                    2025-04-03,A\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let lag1 = build_graph_with(&tmp, &GraphOptions { temporal_lag: 1, ..Default::default() }).unwrap();
+        // A-B same day, plus A(1)-A(2) and A(2)-A(3)
+        assert_eq!(lag1.edge_count(), 3);
+        assert_eq!(component_count(&lag1), 1);
+        let lag2 = build_graph_with(&tmp, &GraphOptions { temporal_lag: 2, ..Default::default() }).unwrap();
+        assert_eq!(lag2.edge_count(), 4);
+    }
+
+    #[test]
+    fn test_area_graph_weights() {
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n\
+                    2025-04-01,B\n\
+                    2025-04-01,C\n\
+                    2025-04-02,A\n\
+                    2025-04-02,B\n\
+                    2025-04-02,B\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let areas = build_area_graph(&tmp).unwrap();
+        assert_eq!(areas.node_count(), 3);
+        assert_eq!(areas.edge_count(), 3);
+        let top = top_cooccurrences(&areas, 1);
+        assert_eq!(top, vec![(("A".to_string(), "B".to_string()), 2)]);
+
+        // Both days fall in one week, so A and B co-occur once per week.
+        let weekly = GraphOptions { bucket: TimeBucket::Week, ..Default::default() };
+        let areas = build_area_graph_with(&tmp, &weekly).unwrap();
+        assert_eq!(top_cooccurrences(&areas, 1), vec![(("A".to_string(), "B".to_string()), 1)]);
+        let lagged = GraphOptions { temporal_lag: 1, ..Default::default() };
+        assert!(matches!(
+            build_area_graph_with(&tmp, &lagged),
+            Err(GraphError::UnsupportedOption { .. })
+        ));
+        // The report's area metrics reject the lag the same way; others keep it.
+        let entries = read_entries(&tmp, &lagged).unwrap();
+        let area_metrics =
+            ReportOptions { metrics: vec![Metric::AreaCooccurrence], ..Default::default() };
+        assert!(matches!(
+            Report::from_entries(&tmp, &entries, &lagged, &area_metrics, false),
+            Err(GraphError::UnsupportedOption { .. })
+        ));
+        let degree = ReportOptions { metrics: vec![Metric::Degree], ..Default::default() };
+        assert!(Report::from_entries(&tmp, &entries, &lagged, &degree, false).is_ok());
+    }
+
+    #[test]
+    fn test_personalize_rejects_unknown_areas() {
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n2025-04-01,B\n\
+                    2025-04-02,B\n2025-04-02,C\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let options = GraphOptions::default();
+        let entries = read_entries(&tmp, &options).unwrap();
+        let personalized = |metrics: Vec<Metric>, names: &[&str]| ReportOptions {
+            metrics,
+            centrality: CentralityOptions {
+                personalize: names.iter().map(|name| name.to_string()).collect(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Unknown names are an error, not silently dropped from the teleport set.
+        let bad = personalized(vec![Metric::AreaCentrality], &["B", "Z", "Q"]);
+        match Report::from_entries(&tmp, &entries, &options, &bad, false) {
+            Err(GraphError::UnknownAreas { unknown, available, .. }) => {
+                assert_eq!(unknown, vec!["Z", "Q"]);
+                assert_eq!(available, vec!["A", "B", "C"]);
+            }
+            other => panic!("expected unknown areas, got {:?}", other.map(|_| ())),
+        }
+        // Without area centrality the option is unused and not checked.
+        let unrelated = personalized(vec![Metric::Degree], &["Z"]);
+        assert!(Report::from_entries(&tmp, &entries, &options, &unrelated, false).is_ok());
+
+        let good = personalized(vec![Metric::AreaCentrality], &["C"]);
+        let report = Report::from_entries(&tmp, &entries, &options, &good, false).unwrap();
+        // Teleporting only to C lifts it above its mirror image A.
+        let pagerank = &report.area_centrality.unwrap().pagerank;
+        let score = |area: &str| pagerank.top.iter().find(|(a, _)| a == area).unwrap().1;
+        assert!(score("C") > score("A"));
+    }
+
+    #[test]
+    fn test_bipartite_projections_and_clustering() {
+        let date1 = NaiveDate::from_ymd_opt(2025, 1, 1).expect("valid date").into();
+        let date2 = NaiveDate::from_ymd_opt(2025, 1, 2).expect("valid date").into();
+        let g = bipartite_from_pairs(vec![
+            (date1, "A"),
+            (date1, "B"),
+            (date2, "A"),
+            (date2, "B"),
+            (date2, "A"),
+        ]);
+        // 2 days + 2 areas, 4 distinct incidences
+        assert_eq!(g.node_count(), 4);
+        assert_eq!(g.edge_count(), 4);
+        let days = project_days(&g);
+        assert_eq!(days.edge_weights().copied().collect::<Vec<_>>(), vec![2]);
+        let areas = project_area_graph(&g);
+        assert_eq!(areas.edge_weights().copied().collect::<Vec<_>>(), vec![2]);
+        // Both days share both areas, so every pair overlaps completely.
+        assert!(bipartite_clustering(&g).iter().all(|&c| (c - 1.0).abs() < 1e-12));
+
+        // The report's bipartite metric comes from the entries, with or without
+        // the materialized clique graph alongside.
+        let entries =
+            vec![(date1, "A".to_string()), (date1, "B".to_string()), (date2, "A".to_string())];
+        let (pairs, options) = (Path::new("pairs.csv"), GraphOptions::default());
+        let only = ReportOptions { metrics: vec![Metric::Bipartite], ..Default::default() };
+        let report = Report::from_entries(pairs, &entries, &options, &only, false).unwrap();
+        let bip = report.bipartite.as_ref().unwrap();
+        assert_eq!((bip.day_nodes, bip.area_nodes, bip.edges, bip.area_pairs), (2, 2, 3, 1));
+        assert_eq!(bip.day_degrees, vec![(1, 1), (2, 1)]);
+        assert_eq!((report.nodes, report.edges), (3, 1));
+        let both = ReportOptions { metrics: vec![Metric::Bipartite, Metric::Components], ..only };
+        let report = Report::from_entries(pairs, &entries, &options, &both, false).unwrap();
+        assert_eq!(report.to_json()["bipartite"], serde_json::json!(bip));
+        assert_eq!(report.components, Some(2));
+
+        // Built from a file, temporal lag has no bipartite meaning and is rejected.
+        let scratch = Scratch::new();
+        let tmp = scratch.csv("DAY,AREA_NAME\n2025-01-01,A\n2025-01-01,B\n2025-01-02,A\n");
+        let built = build_bipartite_graph(&tmp, &GraphOptions::default()).unwrap();
+        assert_eq!((built.node_count(), built.edge_count()), (4, 3));
+        let lagged = GraphOptions { temporal_lag: 1, ..Default::default() };
+        assert!(matches!(
+            build_bipartite_graph(&tmp, &lagged),
+            Err(GraphError::UnsupportedOption { .. })
+        ));
+    }
+
+    #[test]
+    fn test_build_graph_buckets() {
+        let data = "DAY,AREA_NAME,TIME_OCC\n\
+                    2025-04-01,A,930\n\
+                    2025-04-01,B,5\n\
+                    2025-04-02,A,1330\n\
+                    2025-04-30,B,1200\n\
+                    2025-05-01,A,1200\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let build = |bucket| {
+            let options = GraphOptions { bucket, ..Default::default() };
+            build_graph_with(&tmp, &options).unwrap()
+        };
+        // Every row lands in its own hour.
+        assert_eq!(build(TimeBucket::Hour).edge_count(), 0);
+        // 04-01/04-02 fall in ISO week 14, 04-30/05-01 in week 18.
+        let weekly = build(TimeBucket::Week);
+        assert_eq!(weekly.node_count(), 4);
+        assert_eq!(weekly.edge_count(), 2);
+        let labels: Vec<String> = weekly.node_weights().map(|(p, _)| p.to_string()).collect();
+        assert!(labels.contains(&"2025-W18".to_string()));
+        let monthly = build(TimeBucket::Month);
+        assert_eq!(monthly.node_count(), 3);
+        assert_eq!(monthly.edge_count(), 1);
+
+        // Without a time column the hour comes from the date cell, if it has one.
+        let hourly = GraphOptions { bucket: TimeBucket::Hour, ..Default::default() };
+        let stamped = scratch.csv("DAY,AREA_NAME\n2025-04-01 09:30,A\n2025-04-01T09:05:00,B\n");
+        let graph = build_graph_with(&stamped, &hourly).unwrap();
+        assert_eq!((graph.node_count(), graph.edge_count()), (2, 1));
+        assert_eq!(graph[NodeIndex::new(0)].0.to_string(), "2025-04-01T09");
+        // Date-only data has no hour to bucket by.
+        let dates = scratch.csv("DAY,AREA_NAME\n2025-04-01,A\n");
+        let err = build_graph_with(&dates, &hourly).unwrap_err();
+        assert!(matches!(err, GraphError::MissingTime { line: 2, .. }), "{:?}", err);
+        assert!(err.to_string().contains("no TIME_OCC column"), "{}", err);
+        let lenient = GraphOptions { mode: ParseMode::Lenient, ..hourly };
+        assert!(matches!(build_graph_with(&dates, &lenient), Err(GraphError::MissingTime { .. })));
+    }
+
+    #[test]
+    fn test_ingest_raw_cleans_and_dedupes() {
+        let raw = "DR_NO,Date Rptd,DATE OCC,TIME OCC,AREA NAME,Crm Cd Desc,LAT,LON\n\
+                   1,01/02/2020 12:00:00 AM,01/01/2020 12:00:00 AM,2230,Central,THEFT,34.0,-118.2\n\
+                   2,01/02/2020 12:00:00 AM,01/01/2020 12:00:00 AM,15,Central,ASSAULT,34.0,-118.2\n\
+                   3,01/02/2020 12:00:00 AM,01/01/2020 12:00:00 AM,800,Harbor,THEFT,,-118.2\n\
+                   4,01/03/2020 12:00:00 AM,not a date,800,Harbor,THEFT,34.0,-118.2\n\
+                   5,01/03/2020 12:00:00 AM,01/02/2020 12:00:00 AM,800, Harbor ,THEFT,34.0,-118.2\n";
+        let scratch = Scratch::new();
+        let raw_path = scratch.csv(raw);
+        let out_path = scratch.path("day_area.csv");
+        let clean_path = scratch.path("clean.csv");
+        let summary = ingest_raw(&raw_path, &out_path, Some(clean_path.as_path())).unwrap();
+        assert_eq!(summary.raw_rows, 5);
+        assert_eq!(summary.clean_rows, 3);
+        assert_eq!(summary.unique_pairs, 2);
+        let written = std::fs::read_to_string(&out_path).unwrap();
+        assert_eq!(written, "DAY,AREA_NAME\n2020-01-01,Central\n2020-01-02,Harbor\n");
+        let clean = std::fs::read_to_string(&clean_path).unwrap();
+        assert!(clean.starts_with("DR_NO,DATE_OCC,AREA_NAME,LAT,LON,Crm_Cd_Desc,TIME_OCC\n"));
+        let graph = build_graph(&out_path).unwrap();
+        assert_eq!(graph.node_count(), 2);
+    }
+
+    #[test]
+    fn test_build_graph_columns_by_header() {
+        let data = "AREA,EXTRA,DATE\n\
+                    A,x,2025-04-01\n\
+                    B,y,2025-04-01\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let options = GraphOptions {
+            columns: ColumnMap {
+                day: "DATE".to_string(),
+                area: "AREA".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let graph = build_graph_with(&tmp, &options).unwrap();
+        assert_eq!(graph.edge_count(), 1);
+
+        let err = build_graph(&tmp).unwrap_err().to_string();
+        assert!(err.contains("`DAY`"), "{}", err);
+        assert!(err.contains("AREA, EXTRA, DATE"), "{}", err);
+    }
+
+    #[test]
+    fn test_build_graph_error_context() {
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n\
+                    2025-13-01,B\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        match build_graph(&tmp) {
+            Err(GraphError::DateParse { line, value, column, .. }) => {
+                assert_eq!(line, 3);
+                assert_eq!(value, "2025-13-01");
+                assert_eq!(column, "DAY");
+            }
+            other => panic!("expected a date error, got {:?}", other),
+        }
+
+        std::fs::write(&tmp, "DAY,AREA_NAME\n").unwrap();
+        assert!(matches!(build_graph(&tmp), Err(GraphError::EmptyInput { .. })));
+
+        // A ragged row keeps the file and line, with csv's own message once.
+        std::fs::write(&tmp, "DAY,AREA_NAME\n2025-04-01,A\n2025-04-02,B,extra\n").unwrap();
+        let err = build_graph(&tmp).unwrap_err();
+        assert!(matches!(err, GraphError::Csv { line: Some(3), .. }), "{:?}", err);
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "{}:3: CSV error: record 2 (line: 3, byte: 27): found record with 3 fields, \
+                 but the previous record has 2 fields",
+                tmp.display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_lenient_mode_quality_report() {
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,  west   la \n\
+                    2025-04-01,West LA\n\
+                    not-a-date,Central\n\
+                    2025-04-04,\n\
+                    2025-04-04,Gotham\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        assert!(build_graph(&tmp).is_err());
+
+        let options = GraphOptions { mode: ParseMode::Lenient, ..Default::default() };
+        let (entries, quality) = read_entries_checked(&tmp, &options).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(quality.rows_read, 5);
+        assert_eq!(quality.skipped[&SkipReason::InvalidDate], 1);
+        assert_eq!(quality.skipped[&SkipReason::MissingArea], 1);
+        assert_eq!(quality.duplicate_rows, 1);
+        assert_eq!(quality.normalized_areas, 1);
+        assert_eq!(quality.unknown_areas.get("Gotham"), Some(&1));
+        assert_eq!(quality.days_without_data.len(), 2);
+
+        // Rejecting every row keeps the per-reason counts instead of reporting an empty file.
+        let rejected = scratch.csv("DAY,AREA_NAME\nnot-a-date,A\n2025-04-01,\n2025-04-02, \n");
+        match read_entries_checked(&rejected, &options) {
+            Err(err @ GraphError::AllRowsRejected { .. }) => {
+                assert!(err.to_string().contains("all 3 data rows were rejected"), "{}", err);
+                let GraphError::AllRowsRejected { quality, .. } = err else { unreachable!() };
+                assert_eq!(quality.skipped[&SkipReason::InvalidDate], 1);
+                assert_eq!(quality.skipped[&SkipReason::MissingArea], 2);
+            }
+            other => panic!("expected every row rejected, got {:?}", other),
+        }
+        let header_only = scratch.csv("DAY,AREA_NAME\n");
+        assert!(matches!(
+            read_entries_checked(&header_only, &options),
+            Err(GraphError::EmptyInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_clique_graph_matches_materialized() {
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n\
+                    2025-04-01,B\n\
+                    2025-04-01,C\n\
+                    2025-04-02,A\n\
+                    2025-04-02,D\n\
+                    2025-04-03,B\n\
+                    2025-04-05,E\n\
+                    2025-04-05,F\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let options = GraphOptions { temporal_lag: 1, ..Default::default() };
+        let entries = read_entries(&tmp, &options).unwrap();
+        let explicit = graph_from_entries(&entries, &options);
+        let implicit = CliqueGraph::from_entries(&entries, &options);
+
+        assert_eq!(implicit.node_count(), explicit.node_count());
+        assert_eq!(Topology::edge_count(&implicit), explicit.edge_count());
+        assert_eq!(implicit.group_count(), 4);
+        assert_eq!(degree_distribution(&implicit), degree_distribution(&explicit));
+        assert_eq!(component_count(&implicit), component_count(&explicit));
+        for node in implicit.nodes() {
+            assert_eq!(implicit.node(node), &explicit[node]);
+            assert_eq!(bfs_distances(&implicit, node), bfs_distances(&explicit, node));
+        }
+    }
+
+    #[test]
+    fn test_bfs_engine_reuses_buffers() {
+        let mut g: Graph = Graph::new_undirected();
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1)
+            .expect("valid date")
+            .into();
+        let a = g.add_node((date, "A".to_string()));
+        let b = g.add_node((date, "B".to_string()));
+        let c = g.add_node((date, "C".to_string()));
+        let d = g.add_node((date, "D".to_string()));
+        g.add_edge(a, b, ());
+        g.add_edge(b, c, ());
+        let mut engine = BfsEngine::new();
+        let from_a = engine.run(&g, a);
+        assert_eq!((from_a.sum, from_a.reached, from_a.eccentricity), (3, 3, 2));
+        assert_eq!(engine.distance(c), Some(2));
+        // A second run must not see distances left over from the first.
+        let from_d = engine.run(&g, d);
+        assert_eq!((from_d.sum, from_d.reached, from_d.eccentricity), (0, 1, 0));
+        assert_eq!(engine.distance(a), None);
+        assert_eq!(engine.run(&g, b).sum, 2);
+    }
+
+    #[test]
+    fn test_all_pairs_matches_sequential() {
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n\
+                    2025-04-01,B\n\
+                    2025-04-01,C\n\
+                    2025-04-02,A\n\
+                    2025-04-02,D\n\
+                    2025-04-03,D\n\
+                    2025-04-05,E\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let options = GraphOptions { temporal_lag: 1, ..Default::default() };
+        let graph = build_graph_with(&tmp, &options).unwrap();
+
+        let single = all_pairs(&graph, 1);
+        let multi = all_pairs(&graph, 4);
+        assert_eq!(single.per_node, multi.per_node);
+        assert_eq!(single.histogram, multi.histogram);
+        assert_eq!(single.avg_path(), avg_shortest_path(&graph));
+        for kind in [Closeness::Classic, Closeness::WassermanFaust, Closeness::Harmonic] {
+            assert_eq!(
+                top_scores(&graph, &multi.closeness(kind), 3),
+                closeness_centrality_with(&graph, kind, 3)
+            );
+        }
+        // Every reached ordered pair is counted once, including self pairs.
+        let reached: usize = multi.per_node.iter().map(|s| s.reached).sum();
+        assert_eq!(multi.histogram.iter().sum::<u64>(), reached as u64);
+        assert_eq!(multi.histogram[0], graph.node_count() as u64);
+    }
+
+    #[test]
+    fn test_sampled_pairs_seeded_and_exact_at_full_sample() {
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n\
+                    2025-04-01,B\n\
+                    2025-04-01,C\n\
+                    2025-04-02,A\n\
+                    2025-04-02,D\n\
+                    2025-04-03,D\n\
+                    2025-04-03,B\n\
+                    2025-04-04,E\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let options = GraphOptions { temporal_lag: 1, ..Default::default() };
+        let graph = build_graph_with(&tmp, &options).unwrap();
+        let exact = all_pairs(&graph, 1);
+
+        // Sampling every node reproduces the exact values with zero-width intervals.
+        let full = sampled_pairs(&graph, graph.node_count() + 3, 7, 2);
+        assert_eq!(full.pivots.len(), graph.node_count());
+        let avg = full.avg_path.unwrap();
+        assert!((avg.value - exact.avg_path()).abs() < 1e-12);
+        assert!((avg.upper - avg.lower).abs() < 1e-9);
+        for kind in [Closeness::Classic, Closeness::WassermanFaust, Closeness::Harmonic] {
+            for (estimate, score) in full.closeness(kind).iter().zip(exact.closeness(kind)) {
+                assert!((estimate.value - score).abs() < 1e-12);
+                assert!((estimate.upper - estimate.lower).abs() < 1e-9);
+            }
+        }
+
+        // The same seed gives the same sample regardless of threads.
+        let a = sampled_pairs(&graph, 4, 11, 1);
+        let b = sampled_pairs(&graph, 4, 11, 4);
+        assert_eq!(a.pivots, b.pivots);
+        assert_eq!(a.avg_path, b.avg_path);
+        assert_eq!(a.per_node, b.per_node);
+        let ci = a.avg_path.unwrap();
+        assert!(ci.lower <= ci.value && ci.value <= ci.upper);
+
+        // Auto decides by BFS work n·(n + m), and exact is the default.
+        let (n, m) = (graph.node_count(), graph.edge_count());
+        let auto = |max_exact_work| PathMode::Auto { max_exact_work, samples: 4, seed: 11 };
+        assert_eq!(auto((n * (n + m)) as u64).resolve(n, m), PathMode::Exact);
+        assert_eq!(
+            auto((n * (n + m)) as u64 - 1).resolve(n, m),
+            PathMode::Approximate { samples: 4, seed: 11 }
+        );
+        assert_eq!(PathMode::default().resolve(usize::MAX, usize::MAX), PathMode::Exact);
+
+        // Sampled closeness is exported per node with its interval, never ranked.
+        let report_options = ReportOptions {
+            metrics: vec![Metric::AvgPath, Metric::Closeness],
+            path_mode: PathMode::Approximate { samples: 4, seed: 11 },
+            ..Default::default()
+        };
+        let report = Report::compute_with(&graph, &report_options);
+        assert!(report.top_closeness.is_none());
+        let estimates = report.closeness_estimates.as_ref().unwrap();
+        assert_eq!(estimates.len(), n);
+        assert!(estimates.iter().all(|e| e.lower <= e.closeness && e.closeness <= e.upper));
+        let json = report.to_json();
+        assert!(json.get(format!("top{}_closeness", report.top_n).as_str()).is_none());
+        assert_eq!(json["closeness"], "wasserman-faust");
+        let out = scratch.path("report");
+        report.write(&out).unwrap();
+        let csv = std::fs::read_to_string(out.join("closeness_estimates.csv")).unwrap();
+        assert!(csv.starts_with("period,area,closeness,lower,upper\n"));
+        assert_eq!(csv.lines().count(), n + 1);
+
+        // No pivots: unbounded intervals for every node rather than an empty result.
+        let none = sampled_pairs(&graph, 0, 11, 1);
+        assert!(none.avg_path.is_none());
+        let unknown = none.closeness(Closeness::Harmonic);
+        assert_eq!(unknown.len(), n);
+        assert!(unknown.iter().all(|e| e.lower == 0.0 && e.upper == f64::INFINITY));
+        let report_options = ReportOptions {
+            path_mode: PathMode::Approximate { samples: 0, seed: 11 },
+            ..report_options
+        };
+        let report = Report::compute_with(&graph, &report_options);
+        assert_eq!(report.path_sample.as_ref().map(|s| s.pivots), Some(0));
+        assert_eq!(report.closeness_estimates.map(|e| e.len()), Some(n));
+    }
+
+    #[test]
+    fn test_closeness_disconnected_and_tie_break() {
+        // Day 1 triangle {A, B, C}, day 2 pair {C, D} joined to it by the lag
+        // edge (1,C)–(2,C), and an isolated pair {X, Y} on day 10; n = 7.
+        // Rows are reversed so ties cannot fall back on input order.
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-10,Y\n\
+                    2025-04-10,X\n\
+                    2025-04-02,D\n\
+                    2025-04-02,C\n\
+                    2025-04-01,C\n\
+                    2025-04-01,B\n\
+                    2025-04-01,A\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let options = GraphOptions { temporal_lag: 1, ..Default::default() };
+        let graph = build_graph_with(&tmp, &options).unwrap();
+        assert_eq!((graph.node_count(), graph.edge_count()), (7, 6));
+        let close = |(got, want): (f64, f64)| (got - want).abs() < 1e-12;
+        let labels = |top: &[((String, String), f64)]| {
+            top.iter().map(|((d, a), _)| format!("{} {}", d, a)).collect::<Vec<_>>()
+        };
+        let order = vec![
+            "2025-04-01 C",
+            "2025-04-02 C",
+            "2025-04-01 A",
+            "2025-04-01 B",
+            "2025-04-02 D",
+            "2025-04-10 X",
+            "2025-04-10 Y",
+        ];
+
+        // Classic closeness, (n - 1) / Σd, rewards the isolated pair: 6 / 1.
+        let classic = closeness_centrality(&graph, 2);
+        assert_eq!(labels(&classic), vec!["2025-04-10 X", "2025-04-10 Y"]);
+        assert_eq!((classic[0].1, classic[1].1), (6.0, 6.0));
+        assert_eq!(classic, closeness_centrality_with(&graph, Closeness::Classic, 2));
+
+        // Wasserman–Faust, (r - 1)² / ((n - 1) Σd) with r nodes reached:
+        // distance sums are 5, 6, 7, 7, 9 in the big component and 1 in the pair.
+        let wf = closeness_centrality_with(&graph, Closeness::WassermanFaust, 7);
+        assert_eq!(labels(&wf), order);
+        let expected = [16.0 / 30.0, 16.0 / 36.0, 16.0 / 42.0, 16.0 / 42.0, 16.0 / 54.0];
+        assert!(wf.iter().map(|(_, s)| *s).zip(expected).all(close));
+        assert!(close((wf[5].1, 1.0 / 6.0)) && close((wf[6].1, 1.0 / 6.0)));
+
+        // Harmonic, Σ 1/d over n - 1, ranks the same way with the same ties.
+        let harmonic = closeness_centrality_with(&graph, Closeness::Harmonic, 7);
+        assert_eq!(labels(&harmonic), order);
+        let expected = [3.5, 3.0, 17.0 / 6.0, 17.0 / 6.0, 13.0 / 6.0, 1.0, 1.0].map(|h| h / 6.0);
+        assert!(harmonic.iter().map(|(_, s)| *s).zip(expected).all(close));
+    }
+
+    #[test]
+    fn test_betweenness_bridge_nodes_and_edges() {
+        // Day 1 triangle {A, B, C} and day 2 pair {C, D}, joined by the
+        // temporal edge (1,C)–(2,C), which every cross-day path must use.
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n\
+                    2025-04-01,B\n\
+                    2025-04-01,C\n\
+                    2025-04-02,C\n\
+                    2025-04-02,D\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let options = GraphOptions { temporal_lag: 1, ..Default::default() };
+        let graph = build_graph_with(&tmp, &options).unwrap();
+
+        let exact = betweenness(&graph, 2);
+        let scores: Vec<f64> = exact.nodes.clone();
+        // (1,C) separates {A, B} from {(2,C), D}: 2 × 2 pairs;
+        // (2,C) separates {A, B, (1,C)} from D: 3 pairs.
+        assert_eq!(scores, vec![0.0, 0.0, 4.0, 3.0, 0.0]);
+        let top = top_edges(&graph, &exact.edges, 1);
+        assert_eq!(
+            top[0].0,
+            (
+                ("2025-04-01".to_string(), "C".to_string()),
+                ("2025-04-02".to_string(), "C".to_string())
+            )
+        );
+        assert_eq!(top[0].1, 6.0);
+        assert_eq!(exact.edges.len(), graph.edge_count());
+
+        // Sampling every node as a source reproduces the exact scores.
+        let full = sampled_betweenness(&graph, 99, 3, 1);
+        assert_eq!(full.pivots, Some(graph.node_count()));
+        assert_eq!(full.nodes, exact.nodes);
+        assert_eq!(top_edges(&graph, &full.edges, 7), top_edges(&graph, &exact.edges, 7));
+        let top_node = top_scores(&graph, &exact.nodes, 1);
+        assert_eq!(top_node[0].0, ("2025-04-01".to_string(), "C".to_string()));
+
+        // No pivots: zero scores for every node, so ranking them cannot panic.
+        let none = sampled_betweenness(&graph, 0, 3, 1);
+        assert_eq!(none.pivots, Some(0));
+        assert_eq!(none.nodes, vec![0.0; graph.node_count()]);
+        assert_eq!(top_scores(&graph, &none.nodes, 2).len(), 2);
+        let report_options = ReportOptions {
+            metrics: vec![Metric::Betweenness],
+            path_mode: PathMode::Approximate { samples: 0, seed: 3 },
+            ..Default::default()
+        };
+        assert!(Report::compute_with(&graph, &report_options).top_betweenness.is_some());
+    }
+
+    #[test]
+    fn test_graph_from_entries_is_deterministic() {
+        // Enough buckets and areas that hash-ordered construction would
+        // reorder edges, and with them the float sums behind betweenness.
+        let mut data = String::from("DAY,AREA_NAME\n");
+        for day in 1..=9 {
+            for area in ["A", "B", "C", "D", "E", "F", "G"].iter().take(2 + day % 5) {
+                data.push_str(&format!("2025-04-{:02},{}\n", day, area));
+            }
+        }
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(&data);
+        let options = GraphOptions { temporal_lag: 2, ..Default::default() };
+        let entries = read_entries(&tmp, &options).unwrap();
+        let report_options = ReportOptions {
+            metrics: vec![Metric::Betweenness, Metric::Closeness],
+            top_n: 20,
+            ..Default::default()
+        };
+        let runs: Vec<Vec<u8>> = (0..2)
+            .map(|run| {
+                let graph = graph_from_entries(&entries, &options);
+                let out = scratch.path(&format!("run{}", run));
+                Report::compute_with(&graph, &report_options).write(&out).unwrap();
+                std::fs::read(out.join("metrics.json")).unwrap()
+            })
+            .collect();
+        assert_eq!(runs[0], runs[1]);
+        let first = graph_from_entries(&entries, &options);
+        let second = graph_from_entries(&entries, &options);
+        let edges = |g: &Graph| {
+            g.raw_edges().iter().map(|e| (e.source(), e.target())).collect::<Vec<_>>()
+        };
+        assert_eq!(edges(&first), edges(&second));
+        let links = |g: &CliqueGraph| {
+            let mut order = Vec::new();
+            for node in g.nodes() {
+                g.for_each_neighbor(node, |other| order.push((node, other)));
+            }
+            order
+        };
+        assert_eq!(
+            links(&CliqueGraph::from_entries(&entries, &options)),
+            links(&CliqueGraph::from_entries(&entries, &options))
+        );
+    }
+
+    #[test]
+    fn test_pagerank_eigenvector_katz_on_path() {
+        // Weighted path a –2– b –2– c.
+        let mut areas = AreaGraph::new_undirected();
+        let a = areas.add_node("a".to_string());
+        let b = areas.add_node("b".to_string());
+        let c = areas.add_node("c".to_string());
+        areas.add_edge(a, b, 2);
+        areas.add_edge(b, c, 2);
+        let settings = Iteration { tolerance: 1e-12, max_iterations: 10_000 };
+
+        let pr = pagerank(&areas, 0.85, None, settings);
+        assert!(pr.converged && pr.iterations > 1);
+        assert!((pr.scores.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(pr.scores[1] > pr.scores[0]);
+        assert!((pr.scores[0] - pr.scores[2]).abs() < 1e-9);
+        // Teleporting only to `a` lifts it above `c`.
+        let personal = pagerank(&areas, 0.85, Some(&[1.0, 0.0, 0.0]), settings);
+        assert!(personal.scores[0] > personal.scores[2]);
+
+        // Leading eigenvector of the path is (1, √2, 1) / 2 with λ = 2√2.
+        let ev = eigenvector_centrality(&areas, settings);
+        assert!(ev.converged);
+        assert!((ev.scores[1] - 2f64.sqrt() / 2.0).abs() < 1e-6);
+        assert!((largest_eigenvalue(&areas, &ev) - 2.0 * 2f64.sqrt()).abs() < 1e-6);
+
+        // x = αAx + 1: x_a = 1 + 2α·x_b and x_b = 1 + 4α·x_a.
+        let alpha = 0.1;
+        let katz = katz_centrality(&areas, alpha, 1.0, settings);
+        assert!(katz.converged);
+        let x_a = (1.0 + 2.0 * alpha) / (1.0 - 8.0 * alpha * alpha);
+        let x_b = 1.0 + 4.0 * alpha * x_a;
+        assert!((katz.scores[1] / katz.scores[0] - x_b / x_a).abs() < 1e-9);
+
+        // An alpha above 1 / λ diverges and reports it.
+        let capped = Iteration { tolerance: 1e-12, max_iterations: 50 };
+        let diverged = katz_centrality(&areas, 1.0, 1.0, capped);
+        assert!(!diverged.converged);
+        assert_eq!(diverged.iterations, 50);
+        // Left to run, it stops at the overflow and keeps the last finite scores.
+        let long = Iteration { tolerance: 1e-12, max_iterations: 5_000 };
+        let overflowed = katz_centrality(&areas, 1.0, 1.0, long);
+        assert!(!overflowed.converged && overflowed.iterations < 5_000);
+        assert!(overflowed.scores.iter().all(|x| x.is_finite() && *x > 0.0));
+    }
+
+    #[test]
+    fn test_katz_alpha_above_limit_is_rejected() {
+        // Days {A, B} twice and {B, C} twice: the weighted path a –2– b –2– c, λ = 2√2.
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n2025-04-01,B\n2025-04-02,A\n2025-04-02,B\n\
+                    2025-04-03,B\n2025-04-03,C\n2025-04-04,B\n2025-04-04,C\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let options = GraphOptions::default();
+        let entries = read_entries(&tmp, &options).unwrap();
+        let katz = |alpha: f64| ReportOptions {
+            metrics: vec![Metric::AreaCentrality],
+            centrality: CentralityOptions { katz_alpha: Some(alpha), ..Default::default() },
+            ..Default::default()
+        };
+        let limit = 1.0 / (2.0 * 2f64.sqrt());
+
+        let err = Report::from_entries(&tmp, &entries, &options, &katz(1.0), false).unwrap_err();
+        match &err {
+            GraphError::UnsupportedOption { option, .. } => assert_eq!(option, "katz_alpha"),
+            other => panic!("expected an unsupported katz_alpha, got {:?}", other),
+        }
+        assert!(err.to_string().contains("unless alpha is below 1 / λ = 3.536e-1"), "{}", err);
+        let report =
+            Report::from_entries(&tmp, &entries, &options, &katz(0.9 * limit), false).unwrap();
+        assert!(report.area_centrality.unwrap().katz.converged);
+    }
+
+    #[test]
+    fn test_clustering_and_triangles() {
+        // Day 1 triangle {A, B, C}; day 2 pair {A, B}, linked to day 1 by lag.
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n\
+                    2025-04-01,B\n\
+                    2025-04-01,C\n\
+                    2025-04-02,A\n\
+                    2025-04-02,B\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let options = GraphOptions { temporal_lag: 1, ..Default::default() };
+        let graph = build_graph_with(&tmp, &options).unwrap();
+
+        let stats = clustering(&graph);
+        assert_eq!(stats.triangles, vec![1, 1, 1, 0, 0]);
+        assert_eq!(stats.triangle_count, 1);
+        assert_eq!(stats.local, vec![1.0 / 3.0, 1.0 / 3.0, 1.0, 0.0, 0.0]);
+        assert!((stats.average - 1.0 / 3.0).abs() < 1e-12);
+        // Connected triples: 3 + 3 + 1 + 1 + 1.
+        assert!((stats.transitivity - 3.0 / 9.0).abs() < 1e-12);
+        let entries = read_entries(&tmp, &options).unwrap();
+        assert_eq!(clustering(&CliqueGraph::from_entries(&entries, &options)), stats);
+
+        let report = Report::compute(&graph, &[Metric::Clustering], 5);
+        let json = report.to_json();
+        assert_eq!(json["clustering"]["triangles"], 1);
+        assert!(json["clustering"].get("nodes").is_none());
+        let out = scratch.path("report");
+        let written = report.write(&out).unwrap();
+        let csv = std::fs::read_to_string(out.join("clustering.csv")).unwrap();
+        assert!(written.contains(&out.join("clustering.csv")));
+        assert!(csv.starts_with("period,area,degree,triangles,clustering\n2025-04-01,A,3,1,"));
+    }
+
+    #[test]
+    fn test_louvain_two_blocs() {
+        // Two weighted triangles joined by one light edge.
+        let mut areas = AreaGraph::new_undirected();
+        let nodes: Vec<_> = ["a", "b", "c", "x", "y", "z"]
+            .iter()
+            .map(|name| areas.add_node(name.to_string()))
+            .collect();
+        let edges = [(0, 1, 5), (1, 2, 5), (0, 2, 5), (3, 4, 5), (4, 5, 5), (3, 5, 5), (2, 3, 1)];
+        for (i, j, w) in edges {
+            areas.add_edge(nodes[i], nodes[j], w);
+        }
+        let partition = louvain(&areas, 1.0);
+        assert_eq!(partition.membership, vec![0, 0, 0, 1, 1, 1]);
+        // m = 31: each side has 15 inside and degree 31.
+        let expected = 2.0 * (30.0 / 62.0 - (31.0f64 / 62.0).powi(2));
+        assert!((partition.modularity - expected).abs() < 1e-12);
+        assert_eq!(modularity(&areas, &partition.membership, 1.0), partition.modularity);
+        // Everything in one community scores 0.
+        assert!(modularity(&areas, &[0; 6], 1.0).abs() < 1e-12);
+
+        // The unweighted day–area graph: each day's clique is its own community.
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(
+            "DAY,AREA_NAME\n2025-04-01,A\n2025-04-01,B\n2025-04-01,C\n\
+             2025-04-02,A\n2025-04-02,B\n2025-04-02,C\n",
+        );
+        let options = GraphOptions { temporal_lag: 1, ..Default::default() };
+        let graph = build_graph_with(&tmp, &options).unwrap();
+        assert_eq!(louvain(&graph, 1.0).community_count(), 2);
+
+        let report = Report::compute(&graph, &[Metric::Communities], 5);
+        assert_eq!(report.to_json()["area_communities"]["count"], 1);
+        let out = scratch.path("report");
+        report.write(&out).unwrap();
+        let csv = std::fs::read_to_string(out.join("area_communities.csv")).unwrap();
+        assert_eq!(csv, "area,community\nA,0\nB,0\nC,0\n");
+        assert!(out.join("communities.json").exists());
+    }
+
+    #[test]
+    fn test_label_propagation_seeded() {
+        // Three days with disjoint areas give three separate cliques.
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n2025-04-01,B\n2025-04-01,C\n\
+                    2025-04-03,A\n2025-04-03,B\n2025-04-03,C\n2025-04-03,D\n\
+                    2025-04-05,A\n2025-04-05,B\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let graph = build_graph(&tmp).unwrap();
+
+        let run = label_propagation(&graph, 7, 100);
+        assert!(run.converged);
+        // Largest community first: day 3, then day 1, then day 5.
+        assert_eq!(run.partition.membership, vec![1, 1, 1, 0, 0, 0, 0, 2, 2]);
+        assert_eq!(run, label_propagation(&graph, 7, 100));
+        let entries = read_entries(&tmp, &GraphOptions::default()).unwrap();
+        let implicit = CliqueGraph::from_entries(&entries, &GraphOptions::default());
+        assert_eq!(label_propagation(&implicit, 7, 100).partition, run.partition);
+
+        let a = [0, 0, 1, 1];
+        assert_eq!(normalized_mutual_information(&a, &[1, 1, 0, 0]), 1.0);
+        assert!(normalized_mutual_information(&a, &[0, 1, 0, 1]).abs() < 1e-12);
+
+        let report = Report::compute(&graph, &[Metric::LabelPropagation], 5);
+        let lp = report.label_propagation.unwrap();
+        assert_eq!(lp.communities, 3);
+        assert_eq!(lp.size_distribution, vec![(2, 1), (3, 1), (4, 1)]);
+        assert_eq!(lp.community_counts, vec![3; 5]);
+        assert_eq!(lp.min_nmi, 1.0);
+        assert!((lp.largest_share - 4.0 / 9.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_core_decomposition() {
+        // Day 1 is a 4-clique (core 3); day 2's pair closes a 4-cycle with it
+        // through lag edges (core 2); day 5 is an isolated node (core 0).
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n2025-04-01,B\n2025-04-01,C\n2025-04-01,D\n\
+                    2025-04-02,A\n2025-04-02,B\n\
+                    2025-04-05,E\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let options = GraphOptions { temporal_lag: 1, ..Default::default() };
+        let graph = build_graph_with(&tmp, &options).unwrap();
+
+        let cores = core_decomposition(&graph);
+        assert_eq!(cores.core, vec![3, 3, 3, 3, 2, 2, 0]);
+        assert_eq!(cores.max_core, 3);
+        assert_eq!(cores.shells, vec![(0, 1), (2, 2), (3, 4)]);
+        let entries = read_entries(&tmp, &options).unwrap();
+        assert_eq!(core_decomposition(&CliqueGraph::from_entries(&entries, &options)), cores);
+
+        let report = Report::compute(&graph, &[Metric::KCore], 5);
+        assert_eq!(report.to_json()["max_core"], 3);
+        let out = scratch.path("report");
+        report.write(&out).unwrap();
+        let csv = std::fs::read_to_string(out.join("core_counts.csv")).unwrap();
+        assert_eq!(csv, "core,count\n0,1\n2,2\n3,4\n");
+        let csv = std::fs::read_to_string(out.join("core_numbers.csv")).unwrap();
+        assert_eq!(
+            csv,
+            "period,area,core\n\
+             2025-04-01,A,3\n2025-04-01,B,3\n2025-04-01,C,3\n2025-04-01,D,3\n\
+             2025-04-02,A,2\n2025-04-02,B,2\n\
+             2025-04-05,E,0\n"
+        );
+    }
+
+    #[test]
+    fn test_eccentricity_and_component_shapes() {
+        // A 5-node path (1A)–(2A)–(3A)–(4A)–(5A) via lag edges, plus an isolated pair.
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n2025-04-02,A\n2025-04-03,A\n2025-04-04,A\n2025-04-05,A\n\
+                    2025-04-10,X\n2025-04-10,Y\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let options = GraphOptions { temporal_lag: 1, ..Default::default() };
+        let graph = build_graph_with(&tmp, &options).unwrap();
+
+        let eccentricity = all_pairs(&graph, 2).eccentricity();
+        assert_eq!(eccentricity, vec![4, 3, 2, 3, 4, 1, 1]);
+        let membership = component_membership(&graph);
+        assert_eq!(membership, vec![0, 0, 0, 0, 0, 1, 1]);
+        let shapes = component_shapes(&membership, &eccentricity);
+        assert_eq!((shapes[0].diameter, shapes[0].radius), (4, 2));
+        assert_eq!(shapes[0].center, vec![NodeIndex::new(2)]);
+        assert_eq!(shapes[0].periphery, vec![NodeIndex::new(0), NodeIndex::new(4)]);
+        assert_eq!((shapes[1].diameter, shapes[1].radius, shapes[1].center.len()), (1, 1, 2));
+
+        // Eccentricity forces the exact pass even when sampling is requested.
+        let report_options = ReportOptions {
+            metrics: vec![Metric::Eccentricity, Metric::AvgPath],
+            path_mode: PathMode::Approximate { samples: 2, seed: 1 },
+            ..Default::default()
+        };
+        let report = Report::compute_with(&graph, &report_options);
+        assert!(report.path_sample.is_none());
+        assert_eq!(report.avg_path, Some(avg_shortest_path(&graph)));
+        let json = report.to_json();
+        assert_eq!(json["eccentricity"]["diameter"], 4);
+        assert_eq!(json["eccentricity"]["largest_component"]["size"], 5);
+        assert_eq!(
+            json["eccentricity"]["largest_component"]["center"],
+            serde_json::json!([["2025-04-03", "A"]])
+        );
+        let out = scratch.path("report");
+        report.write(&out).unwrap();
+        let csv = std::fs::read_to_string(out.join("component_shapes.csv")).unwrap();
+        assert_eq!(
+            csv,
+            "component,size,diameter,radius,center_size,periphery_size\n0,5,4,2,1,2\n1,2,1,1,2,2\n"
+        );
+    }
+
+    #[test]
+    fn test_component_summary_and_spans() {
+        // Days 1–2 share area A through a lag edge; day 5 stands alone.
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n2025-04-01,B\n\
+                    2025-04-02,A\n2025-04-02,C\n\
+                    2025-04-05,D\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let options = GraphOptions { temporal_lag: 1, ..Default::default() };
+        let graph = build_graph_with(&tmp, &options).unwrap();
+
+        let membership = component_membership(&graph);
+        assert_eq!(membership, vec![0, 0, 0, 0, 1]);
+        let spans = component_spans(&graph, &membership);
+        assert_eq!(spans[0].size, 4);
+        assert_eq!(spans[0].first.to_string(), "2025-04-01");
+        assert_eq!(spans[0].last.to_string(), "2025-04-02");
+        assert_eq!(spans[0].periods, 2);
+        assert_eq!(spans[0].areas.iter().collect::<Vec<_>>(), vec!["A", "B", "C"]);
+
+        let report = Report::compute(&graph, &[Metric::Components], 5);
+        assert_eq!(report.components, Some(component_count(&graph)));
+        let json = report.to_json();
+        assert_eq!(json["component_summary"]["largest_size"], 4);
+        assert_eq!(json["component_summary"]["largest_share"], 0.8);
+        let sizes = &json["component_summary"]["size_distribution"];
+        assert_eq!(sizes, &serde_json::json!([[1, 1], [4, 1]]));
+        let out = scratch.path("report");
+        report.write(&out).unwrap();
+        let csv = std::fs::read_to_string(out.join("components.csv")).unwrap();
+        assert_eq!(
+            csv,
+            "component,size,first_period,last_period,periods,area_count,areas\n\
+             0,4,2025-04-01,2025-04-02,2,3,A;B;C\n\
+             1,1,2025-04-05,2025-04-05,1,1,D\n"
+        );
+        let csv = std::fs::read_to_string(out.join("component_membership.csv")).unwrap();
+        assert_eq!(
+            csv,
+            "period,area,component\n\
+             2025-04-01,A,0\n2025-04-01,B,0\n2025-04-02,A,0\n2025-04-02,C,0\n\
+             2025-04-05,D,1\n"
+        );
+    }
+
+    #[test]
+    fn test_degree_assortativity() {
+        let data = "DAY,AREA_NAME\n\
+                    2025-04-01,A\n2025-04-01,B\n2025-04-01,C\n\
+                    2025-04-02,A\n2025-04-02,B\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        // Without lag edges a triangle and a pair: every edge joins equal degrees.
+        let graph = build_graph(&tmp).unwrap();
+        assert!((degree_assortativity(&graph).unwrap() - 1.0).abs() < 1e-9);
+        let options = GraphOptions { temporal_lag: 1, ..Default::default() };
+        let entries = read_entries(&tmp, &options).unwrap();
+        let first_day = entries.iter().take(3).cloned().collect::<Vec<_>>();
+        let triangle = CliqueGraph::from_entries(&first_day, &GraphOptions::default());
+        assert_eq!(degree_assortativity(&triangle), None);
+
+        // Lag edges tie the degree-3 pair of day 1 to degree-2 nodes.
+        let graph = build_graph_with(&tmp, &options).unwrap();
+        let r = degree_assortativity(&graph).unwrap();
+        assert!((r + 1.0 / 3.0).abs() < 1e-9, "r = {}", r);
+        let curve = avg_neighbor_degree(&graph);
+        let counts = curve.iter().map(|&(k, _, n)| (k, n)).collect::<Vec<_>>();
+        assert_eq!(counts, vec![(2, 3), (3, 2)]);
+        assert!((curve[0].1 - 16.0 / 6.0).abs() < 1e-9);
+        assert!((curve[1].1 - 14.0 / 6.0).abs() < 1e-9);
+        let clique = CliqueGraph::from_entries(&entries, &options);
+        assert!((degree_assortativity(&clique).unwrap() - r).abs() < 1e-9);
+
+        let report = Report::compute(&graph, &[Metric::Assortativity], 5);
+        let json = report.to_json();
+        assert!((json["degree_assortativity"].as_f64().unwrap() - r).abs() < 1e-9);
+        assert_eq!(json["avg_neighbor_degree"][1][2], 2);
+        let out = scratch.path("report");
+        report.write(&out).unwrap();
+        let csv = std::fs::read_to_string(out.join("neighbor_degree.csv")).unwrap();
+        assert!(csv.starts_with("degree,avg_neighbor_degree,count\n2,"));
+    }
+
+    #[test]
+    fn test_fit_degrees() {
+        let distribution = [
+            (0, 7), (1, 500), (2, 180), (3, 90), (4, 60), (5, 40), (6, 28), (8, 20),
+            (10, 14), (13, 9), (16, 6), (20, 4), (30, 2), (50, 1),
+        ];
+        // A tail minimum of every positive degree forces x_min = 1; references
+        // from direct zeta sums, the closed-form λ and a local grid search.
+        let fits = fit_degrees(&distribution, 954).unwrap();
+        assert_eq!((fits.x_min, fits.tail), (1, 954));
+        assert!((fits.power_law.alpha - 1.98386).abs() < 1e-4, "{:?}", fits.power_law);
+        assert!((fits.power_law.log_likelihood + 1589.9105).abs() < 1e-3);
+        assert!((fits.exponential.lambda - 0.495647).abs() < 1e-6);
+        assert!((fits.log_normal.mu + 0.5831).abs() < 1e-3, "{:?}", fits.log_normal);
+        assert!((fits.log_normal.sigma - 1.2563).abs() < 1e-3);
+        assert!((fits.log_normal.log_likelihood + 1547.4341).abs() < 1e-3);
+        // The log ratio is the difference of the models' log-likelihoods; the
+        // power law's lead over the exponential falls short of p < 0.1.
+        let first = &fits.comparisons[0];
+        let gap = fits.power_law.log_likelihood - fits.exponential.log_likelihood;
+        assert!((first.log_ratio - gap).abs() < 1e-9);
+        let favored: Vec<_> = fits.comparisons.iter().map(|c| c.favored).collect();
+        assert_eq!(favored, vec![None, Some("log-normal"), Some("log-normal")]);
+
+        let chosen = fit_degrees(&distribution, 50).unwrap();
+        assert!(chosen.power_law.ks <= fits.power_law.ks);
+        assert!(chosen.tail >= 50);
+        assert_eq!(fit_degrees(&distribution, 955), None);
+        assert_eq!(fit_degrees(&[(0, 3), (4, 10)], 1), None);
+
+        let data = "DAY,AREA_NAME\n2025-04-01,A\n2025-04-01,B\n2025-04-02,A\n";
+        let scratch = Scratch::new();
+        let tmp = scratch.csv(data);
+        let graph = build_graph(&tmp).unwrap();
+        let report = Report::compute(&graph, &[Metric::DegreeFit], 5);
+        assert_eq!(report.degree_distribution, None);
+        assert_eq!(report.to_json()["degree_fit"], serde_json::Value::Null);
+    }
+}
@@ -1,140 +1,140 @@
 //! DS210 Final Project (Modular): LA Crime‐Graph Analysis
 //!
-//! - Builds graph from `data/day_area.csv` (one node per (DAY,AREA_NAME))
+//! - Cleans a raw LAPD export into a (DAY,AREA_NAME) CSV (`ingest`)
+//! - Builds graph from a (DAY,AREA_NAME) CSV (one node per pair)
 //! - Computes degree distribution, BFS‐based avg‐path, closeness, and components
-//! - Exports `report/metrics.json` and `report/degree_counts.csv`
-
-mod graph;
-mod analysis;
-
-use crate::graph::build_graph;
-use crate::analysis::{
-    degree_distribution,
-    avg_shortest_path,
-    closeness_centrality,
-    component_count,
+//! - Exports `metrics.json`, `degree_counts.csv` and `data_quality.json` into the output directory
+//!
+//! Run `final_project --help` for the `ingest`, `build`, `analyze`, `export` and `report`
+//! subcommands.
+
+mod cli;
+
+use crate::cli::{AnalysisArgs, Cli, Command, Format, InputArgs, Model};
+use final_project::{
+    build_bipartite_graph,
+    build_clique_graph,
+    build_graph_with,
+    ingest_raw,
+    read_entries_checked,
+    BipartiteSummary,
+    GraphError,
+    Report,
+    Topology,
 };
-use itertools::Itertools;
-use csv::Writer;
-use serde_json::json;
-use std::{error::Error, fs};
-
-fn main() -> Result<(), Box<dyn Error>> {
-    // Build the graph
-    let graph = build_graph("data/day_area.csv")?;
-    println!(
-        "Graph built: {} nodes, {} edges",
-        graph.node_count(),
-        graph.edge_count()
-    );
-
-    // Degree distribution
-    let deg_dist = degree_distribution(&graph);
-    println!("Degree distribution:");
-    for (d, cnt) in deg_dist.iter().sorted_by_key(|&(d, _)| *d) {
-        println!("  {} → {}", d, cnt);
+use clap::Parser;
+use std::error::Error;
+use std::path::Path;
+
+fn main() {
+    // Print errors with Display so file/line context reaches the operator.
+    if let Err(err) = run(Cli::parse()) {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
     }
+}
 
-    // Average shortest-path length
-    let avg = avg_shortest_path(&graph);
-    println!("Avg shortest-path length: {:.3}", avg);
-
-    // Top-5 closeness centrality
-    let top5 = closeness_centrality(&graph, 5);
-    println!("Top 5 closeness centrality:");
-    for ((day, area), score) in &top5 {
-        println!("  {} | {} → {:.4}", day, area, score);
-    }
-
-    // Connected components
-    let comps = component_count(&graph);
-    println!("Connected components: {}", comps);
-
-    // Write JSON metrics
-    fs::create_dir_all("report")?;
-    let metrics = json!({
-        "nodes": graph.node_count(),
-        "edges": graph.edge_count(),
-        "avg_path": avg,
-        "top5_closeness": top5,
-        "components": comps
-    });
-    fs::write(
-        "report/metrics.json",
-        serde_json::to_string_pretty(&metrics)?,
-    )?;
-    println!("report/metrics.json written");
-
-    // Write degree_counts.csv
-    let mut wtr = Writer::from_path("report/degree_counts.csv")?;
-    wtr.write_record(&["degree", "count"])?;
-    for (d, cnt) in deg_dist {
-        wtr.write_record(&[d.to_string(), cnt.to_string()])?;
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    match cli.command {
+        Command::Ingest { raw, output, clean_output } => {
+            let summary = ingest_raw(&raw, &output, clean_output.as_deref())?;
+            println!(
+                "Rows read: {}, after cleaning: {}",
+                summary.raw_rows, summary.clean_rows
+            );
+            if let Some(path) = &clean_output {
+                println!("{} written", path.display());
+            }
+            println!(
+                "Rows before: {}, after dedupe: {}",
+                summary.clean_rows, summary.unique_pairs
+            );
+            println!("{} written", output.display());
+        }
+        Command::Build { input, model: Model::Clique } if input.implicit => {
+            let graph = build_clique_graph(&input.input, &input.graph_options())?;
+            println!(
+                "Graph built: {} nodes, {} edges ({} implicit cliques)",
+                graph.node_count(),
+                graph.edge_count(),
+                graph.group_count()
+            );
+        }
+        Command::Build { input, model: Model::Clique } => {
+            let graph = build_graph_with(&input.input, &input.graph_options())?;
+            println!(
+                "Graph built: {} nodes, {} edges",
+                graph.node_count(),
+                graph.edge_count()
+            );
+        }
+        Command::Build { input, model: Model::Bipartite } if input.implicit => {
+            return Err(GraphError::UnsupportedOption {
+                path: input.input,
+                option: "--implicit".to_string(),
+                reason: "the bipartite graph has no cliques to keep implicit".to_string(),
+            }
+            .into());
+        }
+        Command::Build { input, model: Model::Bipartite } => {
+            let graph = build_bipartite_graph(&input.input, &input.graph_options())?;
+            let summary = BipartiteSummary::of(&graph);
+            println!(
+                "Bipartite graph built: {} day nodes, {} area nodes, {} edges",
+                summary.day_nodes, summary.area_nodes, summary.edges
+            );
+            summary.print_text();
+        }
+        Command::Analyze { input, analysis, format } => {
+            let report = analyze(&input, &analysis)?;
+            match format {
+                Format::Text => report.print_text(),
+                Format::Json => {
+                    println!("{}", serde_json::to_string_pretty(&report.to_json())?)
+                }
+            }
+        }
+        Command::Export { input, analysis, output } => {
+            let report = analyze_or_keep_quality(&input, &analysis, &output.out_dir)?;
+            for path in report.write(&output.out_dir)? {
+                println!("{} written", path.display());
+            }
+        }
+        Command::Report { input, analysis, output } => {
+            let report = analyze_or_keep_quality(&input, &analysis, &output.out_dir)?;
+            report.print_text();
+            for path in report.write(&output.out_dir)? {
+                println!("{} written", path.display());
+            }
+        }
     }
-    wtr.flush()?;
-    println!("report/degree_counts.csv written");
 
     Ok(())
 }
 
-
-#[cfg(test)]
-mod tests {
-    use crate::graph::{build_graph, bfs_distances, Graph};
-    use crate::analysis::{degree_distribution, component_count};
-    use chrono::NaiveDate;
-
-    #[test]
-    fn test_bfs_chain() {
-        let mut g: Graph = Graph::new_undirected();
-        let date = NaiveDate::from_ymd_opt(2025, 1, 1)
-            .expect("valid date");
-        let a = g.add_node((date, "A".to_string()));
-        let b = g.add_node((date, "B".to_string()));
-        let c = g.add_node((date, "C".to_string()));
-        g.add_edge(a, b, ());
-        g.add_edge(b, c, ());
-        let dist = bfs_distances(&g, a);
-        assert_eq!(dist.get(&a), Some(&0));
-        assert_eq!(dist.get(&b), Some(&1));
-        assert_eq!(dist.get(&c), Some(&2));
-    }
-
-    #[test]
-    fn test_degree_distribution_triangle() {
-        let mut g: Graph = Graph::new_undirected();
-        let date = NaiveDate::from_ymd_opt(2025, 1, 1)
-            .expect("valid date");
-        let a = g.add_node((date, "A".to_string()));
-        let b = g.add_node((date, "B".to_string()));
-        let c = g.add_node((date, "C".to_string()));
-        g.add_edge(a, b, ());
-        g.add_edge(b, c, ());
-        g.add_edge(c, a, ());
-        let deg = degree_distribution(&g);
-        assert_eq!(deg.get(&2), Some(&3));
-    }
-
-    #[test]
-    fn test_component_count_isolated() {
-        let mut g: Graph = Graph::new_undirected();
-        let date = NaiveDate::from_ymd_opt(2025, 1, 1)
-            .expect("valid date");
-        g.add_node((date, "X".to_string()));
-        g.add_node((date, "Y".to_string()));
-        assert_eq!(component_count(&g), 2);
+/// Like `analyze`, but when lenient mode rejects every row, still writes the
+/// data-quality report to `out_dir` before failing, since it says why.
+fn analyze_or_keep_quality(
+    input: &InputArgs,
+    analysis: &AnalysisArgs,
+    out_dir: &Path,
+) -> Result<Report, Box<dyn Error>> {
+    match analyze(input, analysis) {
+        Err(GraphError::AllRowsRejected { path, quality }) => {
+            println!("{} written", quality.write(out_dir)?.display());
+            Err(GraphError::AllRowsRejected { path, quality }.into())
+        }
+        result => Ok(result?),
     }
+}
 
-    #[test]
-    fn test_build_graph_tiny() {
-        let data = "DAY,AREA_NAME\n\
-                    2025-04-01,A\n\
-                    2025-04-01,B\n\
-                    2025-04-02,A\n";
-        let tmp = std::env::temp_dir().join("test_day_area.csv");
-        std::fs::write(&tmp, data).unwrap();
-        let graph: Graph = build_graph(tmp.to_str().unwrap()).unwrap();
-        assert_eq!(graph.node_count(), 3);
-        assert_eq!(graph.edge_count(), 1);
-    }
+/// Read the input once and run the selected metrics on the chosen representation,
+/// keeping the input's data-quality summary in the report.
+fn analyze(input: &InputArgs, analysis: &AnalysisArgs) -> Result<Report, GraphError> {
+    let options = input.graph_options();
+    let (entries, quality) = read_entries_checked(&input.input, &options)?;
+    let report_options = analysis.report_options(options.mode);
+    let report =
+        Report::from_entries(&input.input, &entries, &options, &report_options, input.implicit)?;
+    Ok(report.with_quality(quality))
 }
@@ -0,0 +1,143 @@
+// src/period.rs
+
+//! Hour, day, week and month time buckets, the time part of every node.
+
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use serde::{Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// Granularity used to group incidents into graph nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum TimeBucket {
+    /// Clock hour.
+    Hour,
+    /// Calendar day.
+    #[default]
+    Day,
+    /// ISO week, starting on Monday.
+    Week,
+    /// Calendar month.
+    Month,
+}
+
+impl TimeBucket {
+    /// The bucket containing `date` (and `time`, which only matters for `Hour`).
+    pub fn period(self, date: NaiveDate, time: NaiveTime) -> Period {
+        let start = match self {
+            TimeBucket::Hour => date.and_hms_opt(time.hour(), 0, 0),
+            TimeBucket::Day => date.and_hms_opt(0, 0, 0),
+            TimeBucket::Week => {
+                let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+                monday.and_hms_opt(0, 0, 0)
+            }
+            TimeBucket::Month => date.with_day(1).and_then(|d| d.and_hms_opt(0, 0, 0)),
+        }
+        .expect("bucket start is a valid datetime");
+        Period { start, bucket: self }
+    }
+}
+
+impl FromStr for TimeBucket {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "hour" => Ok(TimeBucket::Hour),
+            "day" => Ok(TimeBucket::Day),
+            "week" => Ok(TimeBucket::Week),
+            "month" => Ok(TimeBucket::Month),
+            other => Err(format!(
+                "unknown time bucket `{}` (expected hour, day, week or month)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for TimeBucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TimeBucket::Hour => "hour",
+            TimeBucket::Day => "day",
+            TimeBucket::Week => "week",
+            TimeBucket::Month => "month",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Serialize for TimeBucket {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// One time bucket: its start instant and granularity.
+///
+/// Displays as `2025-04-01T13` (hour), `2025-04-01` (day), `2025-W14` (ISO week)
+/// or `2025-04` (month), which is also how it is serialized in reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Period {
+    /// First instant of the bucket.
+    pub start: NaiveDateTime,
+    /// Granularity of the bucket.
+    pub bucket: TimeBucket,
+}
+
+impl Period {
+    /// The bucket `k` steps after this one.
+    pub fn offset(self, k: u32) -> Period {
+        let start = match self.bucket {
+            TimeBucket::Hour => self.start + Duration::hours(k as i64),
+            TimeBucket::Day => self.start + Duration::days(k as i64),
+            TimeBucket::Week => self.start + Duration::weeks(k as i64),
+            TimeBucket::Month => self
+                .start
+                .checked_add_months(Months::new(k))
+                .expect("month offset in range"),
+        };
+        Period { start, ..self }
+    }
+
+    /// Calendar date the bucket starts on.
+    pub fn date(&self) -> NaiveDate {
+        self.start.date()
+    }
+}
+
+impl From<NaiveDate> for Period {
+    fn from(date: NaiveDate) -> Self {
+        TimeBucket::Day.period(date, NaiveTime::MIN)
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.bucket {
+            TimeBucket::Hour => write!(f, "{}", self.start.format("%Y-%m-%dT%H")),
+            TimeBucket::Day => write!(f, "{}", self.start.format("%Y-%m-%d")),
+            TimeBucket::Week => {
+                let week = self.start.date().iso_week();
+                write!(f, "{}-W{:02}", week.year(), week.week())
+            }
+            TimeBucket::Month => write!(f, "{}", self.start.format("%Y-%m")),
+        }
+    }
+}
+
+impl Serialize for Period {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Parse an LAPD `TIME_OCC` value: 24-hour `HHMM` without leading zeros
+/// (`5` is 00:05, `1330` is 13:30), or `HH:MM`.
+pub fn parse_time_occ(value: &str) -> Option<NaiveTime> {
+    let value = value.trim();
+    if let Ok(time) = NaiveTime::parse_from_str(value, "%H:%M") {
+        return Some(time);
+    }
+    let hhmm: u32 = value.parse().ok()?;
+    NaiveTime::from_hms_opt(hhmm / 100, hhmm % 100, 0)
+}
@@ -0,0 +1,154 @@
+// src/quality.rs
+
+//! Strict and lenient parsing and the data-quality report of an input file.
+
+use chrono::{Duration, NaiveDate};
+use itertools::Itertools;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs};
+
+/// The 21 LAPD geographic areas, as spelled in the `AREA NAME` column.
+pub const KNOWN_AREAS: [&str; 21] = [
+    "77th Street", "Central", "Devonshire", "Foothill", "Harbor", "Hollenbeck",
+    "Hollywood", "Mission", "N Hollywood", "Newton", "Northeast", "Olympic",
+    "Pacific", "Rampart", "Southeast", "Southwest", "Topanga", "Van Nuys",
+    "West LA", "West Valley", "Wilshire",
+];
+
+/// How `read_entries` treats rows it cannot parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Abort on the first bad row.
+    #[default]
+    Strict,
+    /// Skip bad rows, normalize area names and record both in `DataQuality`.
+    Lenient,
+}
+
+impl fmt::Display for ParseMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ParseMode::Strict => "strict",
+            ParseMode::Lenient => "lenient",
+        })
+    }
+}
+
+/// Why a row was skipped in lenient mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// The CSV reader could not parse the record.
+    MalformedRow,
+    /// The day column is not a recognised date.
+    InvalidDate,
+    /// The time column is not a recognised time (hourly buckets only).
+    InvalidTime,
+    /// The area column is empty after normalization.
+    MissingArea,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SkipReason::MalformedRow => "malformed row",
+            SkipReason::InvalidDate => "invalid date",
+            SkipReason::InvalidTime => "invalid time",
+            SkipReason::MissingArea => "missing area",
+        })
+    }
+}
+
+/// Data-quality summary of one input file, written as `data_quality.json`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DataQuality {
+    /// Parse mode the file was read in, `strict` or `lenient`.
+    pub mode: String,
+    /// Data rows read, header excluded.
+    pub rows_read: usize,
+    /// Rows that made it into the graph.
+    pub rows_kept: usize,
+    /// Skipped rows by reason.
+    pub skipped: BTreeMap<SkipReason, usize>,
+    /// Kept rows whose (day, area) had already been seen.
+    pub duplicate_rows: usize,
+    /// Kept rows whose area name was changed by normalization.
+    pub normalized_areas: usize,
+    /// Area names outside `KNOWN_AREAS`, with their row counts.
+    pub unknown_areas: BTreeMap<String, usize>,
+    /// Earliest kept day.
+    pub first_day: Option<NaiveDate>,
+    /// Latest kept day.
+    pub last_day: Option<NaiveDate>,
+    /// Days between `first_day` and `last_day` without any row.
+    pub days_without_data: Vec<NaiveDate>,
+    #[serde(skip)]
+    seen: BTreeSet<(NaiveDate, String)>,
+}
+
+impl DataQuality {
+    /// An empty summary for a file read in `mode`.
+    pub fn new(mode: ParseMode) -> Self {
+        DataQuality {
+            mode: mode.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Record a skipped row.
+    pub fn skip(&mut self, reason: SkipReason) {
+        *self.skipped.entry(reason).or_default() += 1;
+    }
+
+    /// Record a kept row.
+    pub fn keep(&mut self, day: NaiveDate, area: &str) {
+        self.rows_kept += 1;
+        if !self.seen.insert((day, area.to_string())) {
+            self.duplicate_rows += 1;
+        }
+        if !KNOWN_AREAS.contains(&area) {
+            *self.unknown_areas.entry(area.to_string()).or_default() += 1;
+        }
+    }
+
+    /// Total rows skipped for any reason.
+    pub fn skipped_rows(&self) -> usize {
+        self.skipped.values().sum()
+    }
+
+    /// Writes the summary as `data_quality.json` in `out_dir` and returns its path.
+    pub fn write(&self, out_dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        fs::create_dir_all(out_dir)?;
+        let path = out_dir.join("data_quality.json");
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    /// Compute the date range and the gaps in it; call once all rows are recorded.
+    pub fn finish(&mut self) {
+        let days: BTreeSet<NaiveDate> = self.seen.iter().map(|(day, _)| *day).collect();
+        self.first_day = days.first().copied();
+        self.last_day = days.last().copied();
+        self.days_without_data = match (self.first_day, self.last_day) {
+            (Some(first), Some(last)) => (0..=(last - first).num_days())
+                .map(|offset| first + Duration::days(offset))
+                .filter(|day| !days.contains(day))
+                .collect_vec(),
+            _ => Vec::new(),
+        };
+    }
+}
+
+/// Collapse runs of whitespace and match `KNOWN_AREAS` case-insensitively
+/// (`"  west   la "` → `"West LA"`). Unknown names keep their case.
+pub fn normalize_area(raw: &str) -> String {
+    let collapsed = raw.split_whitespace().join(" ");
+    KNOWN_AREAS
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(&collapsed))
+        .map(|known| known.to_string())
+        .unwrap_or(collapsed)
+}
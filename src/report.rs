@@ -0,0 +1,1540 @@
+// src/report.rs
+
+//! Runs a selection of metrics and exports `metrics.json` plus CSV tables.
+
+use crate::graph::{
+    area_projection, graph_from_entries, project_areas, AreaGraph, GraphOptions, Topology,
+};
+use crate::bipartite::{bipartite_from_pairs, bipartite_projection, BipartiteSummary};
+use crate::clique::CliqueGraph;
+use crate::error::GraphError;
+use crate::period::{Period, TimeBucket};
+use crate::quality::DataQuality;
+use crate::fit::{fit_degrees, DegreeFits};
+use crate::community::{label_propagation, louvain, normalized_mutual_information};
+use crate::betweenness::{betweenness, sampled_betweenness, top_edges, EdgeScore};
+use crate::all_pairs::{all_pairs, sampled_pairs, Estimate, PathMode};
+use crate::analysis::{
+    avg_neighbor_degree,
+    degree_assortativity,
+    component_spans,
+    component_membership,
+    component_shapes,
+    core_decomposition,
+    clustering,
+    eigenvector_centrality,
+    katz_centrality,
+    largest_eigenvalue,
+    pagerank,
+    Centrality,
+    Closeness,
+    Iteration,
+    degree_distribution,
+    top_scores,
+    area_strengths,
+    top_cooccurrences,
+};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use csv::Writer;
+use itertools::Itertools;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::{
+    collections::{BTreeSet, HashSet},
+    error::Error,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// A metric that can be selected for analysis or export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// Degree distribution.
+    Degree,
+    /// Average shortest-path length and distance histogram.
+    AvgPath,
+    /// Closeness centrality, top-N or sampled estimates.
+    Closeness,
+    /// Connected components and their spans.
+    Components,
+    /// Area co-occurrence graph: top pairs and area strengths.
+    AreaCooccurrence,
+    /// Day–area bipartite graph summary.
+    Bipartite,
+    /// Node and edge betweenness, top-N.
+    Betweenness,
+    /// PageRank, eigenvector and Katz centrality of the area graph.
+    AreaCentrality,
+    /// Local and average clustering and transitivity.
+    Clustering,
+    /// Louvain communities of the area graph.
+    Communities,
+    /// Label-propagation communities of the (bucket, area) graph.
+    LabelPropagation,
+    /// k-core decomposition.
+    KCore,
+    /// Eccentricity, diameter, radius, center and periphery.
+    Eccentricity,
+    /// Degree assortativity and average neighbour degree.
+    Assortativity,
+    /// Power-law, exponential and log-normal fits of the degree tail.
+    DegreeFit,
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "degree" => Ok(Metric::Degree),
+            "avg-path" => Ok(Metric::AvgPath),
+            "closeness" => Ok(Metric::Closeness),
+            "components" => Ok(Metric::Components),
+            "area-cooccurrence" => Ok(Metric::AreaCooccurrence),
+            "bipartite" => Ok(Metric::Bipartite),
+            "betweenness" => Ok(Metric::Betweenness),
+            "area-centrality" => Ok(Metric::AreaCentrality),
+            "clustering" => Ok(Metric::Clustering),
+            "communities" => Ok(Metric::Communities),
+            "label-propagation" => Ok(Metric::LabelPropagation),
+            "k-core" => Ok(Metric::KCore),
+            "eccentricity" => Ok(Metric::Eccentricity),
+            "assortativity" => Ok(Metric::Assortativity),
+            "degree-fit" => Ok(Metric::DegreeFit),
+            other => Err(format!(
+                "unknown metric `{}` (expected degree, avg-path, closeness, components, \
+                 area-cooccurrence, bipartite, betweenness, area-centrality, clustering, \
+                 communities, label-propagation, k-core, eccentricity, assortativity \
+                 or degree-fit)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Metric::Degree => "degree",
+            Metric::AvgPath => "avg-path",
+            Metric::Closeness => "closeness",
+            Metric::Components => "components",
+            Metric::AreaCooccurrence => "area-cooccurrence",
+            Metric::Bipartite => "bipartite",
+            Metric::Betweenness => "betweenness",
+            Metric::AreaCentrality => "area-centrality",
+            Metric::Clustering => "clustering",
+            Metric::Communities => "communities",
+            Metric::LabelPropagation => "label-propagation",
+            Metric::KCore => "k-core",
+            Metric::Eccentricity => "eccentricity",
+            Metric::Assortativity => "assortativity",
+            Metric::DegreeFit => "degree-fit",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Which metrics `Report::compute_with` runs and how.
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    /// Metrics to run.
+    pub metrics: Vec<Metric>,
+    /// How many entries to keep in top-N rankings.
+    pub top_n: usize,
+    /// Worker threads for the all-pairs BFS pass (0 = one per core).
+    pub threads: usize,
+    /// Exact all-pairs BFS or pivot sampling for average path and closeness.
+    pub path_mode: PathMode,
+    /// Closeness variant ranked in `top{n}_closeness`.
+    pub closeness: Closeness,
+    /// Settings for PageRank, eigenvector and Katz centrality of the area graph.
+    pub centrality: CentralityOptions,
+    /// Louvain resolution for area communities (1.0 = standard modularity).
+    pub resolution: f64,
+    /// Fewest nodes a degree tail may hold when choosing the fits' `x_min`.
+    pub fit_min_tail: usize,
+    /// Settings for label propagation.
+    pub propagation: PropagationOptions,
+}
+
+/// Settings for `Metric::LabelPropagation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropagationOptions {
+    /// Seed of the reported run; run `i` uses `seed + i`.
+    pub seed: u64,
+    /// Runs compared for stability, including the reported one.
+    pub runs: usize,
+    /// Sweeps after which a run stops unconverged.
+    pub max_sweeps: usize,
+}
+
+impl Default for PropagationOptions {
+    fn default() -> Self {
+        PropagationOptions { seed: 42, runs: 5, max_sweeps: 100 }
+    }
+}
+
+/// Settings for `Metric::AreaCentrality`.
+#[derive(Debug, Clone)]
+pub struct CentralityOptions {
+    /// PageRank damping factor.
+    pub damping: f64,
+    /// Areas PageRank teleports to, uniformly; empty means all areas.
+    /// `AreaCentrality::of` ignores names that are not areas of the graph;
+    /// `Report::from_entries` rejects them.
+    pub personalize: Vec<String>,
+    /// Katz attenuation; `None` uses 0.9 / λ for the largest eigenvalue λ.
+    pub katz_alpha: Option<f64>,
+    /// Tolerance and iteration cap shared by the three centralities.
+    pub iteration: Iteration,
+}
+
+impl CentralityOptions {
+    /// Names in `personalize` that are not among `areas`, in option order.
+    pub fn unknown_areas<'a>(&self, areas: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        let known: HashSet<&str> = areas.into_iter().collect();
+        self.personalize.iter().filter(|name| !known.contains(name.as_str())).cloned().collect()
+    }
+}
+
+impl Default for CentralityOptions {
+    fn default() -> Self {
+        CentralityOptions {
+            damping: 0.85,
+            personalize: Vec::new(),
+            katz_alpha: None,
+            iteration: Iteration::default(),
+        }
+    }
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        ReportOptions {
+            metrics: vec![
+                Metric::Degree,
+                Metric::AvgPath,
+                Metric::Closeness,
+                Metric::Components,
+                Metric::AreaCooccurrence,
+            ],
+            top_n: 5,
+            threads: 0,
+            path_mode: PathMode::default(),
+            closeness: Closeness::default(),
+            centrality: CentralityOptions::default(),
+            resolution: 1.0,
+            fit_min_tail: 50,
+            propagation: PropagationOptions::default(),
+        }
+    }
+}
+
+/// Pivot sample behind approximate average path and closeness values.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PathSample {
+    /// Pivots BFS ran from.
+    pub pivots: usize,
+    /// Seed the pivots were drawn with.
+    pub seed: u64,
+    /// 95% interval of `Report::avg_path`.
+    pub avg_path: Option<Estimate>,
+}
+
+/// Sampled closeness of one node with its 95% interval, a row of
+/// `closeness_estimates.csv`; an unbounded `upper` is written as `inf`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClosenessEstimate {
+    /// Time bucket of the node.
+    pub period: String,
+    /// Area of the node.
+    pub area: String,
+    /// Point estimate.
+    pub closeness: f64,
+    /// Lower bound of the interval.
+    pub lower: f64,
+    /// Upper bound of the interval.
+    pub upper: f64,
+}
+
+/// One iterative centrality over the area graph, reduced to its top areas.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RankedCentrality {
+    /// Iterations run.
+    pub iterations: usize,
+    /// Whether the tolerance was reached within the iteration cap.
+    pub converged: bool,
+    /// (area, score) of the highest-scoring areas, best first.
+    pub top: Vec<(String, f64)>,
+}
+
+impl RankedCentrality {
+    /// Keeps the `n` highest-scoring areas, ties broken by name.
+    fn of(areas: &AreaGraph, centrality: &Centrality, n: usize) -> Self {
+        let top = areas
+            .node_indices()
+            .map(|node| (areas[node].clone(), centrality.scores[node.index()]))
+            .sorted_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)))
+            .take(n)
+            .collect();
+        RankedCentrality {
+            iterations: centrality.iterations,
+            converged: centrality.converged,
+            top,
+        }
+    }
+}
+
+/// PageRank, eigenvector and Katz rankings of the area co-occurrence graph.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AreaCentrality {
+    /// PageRank damping factor.
+    pub damping: f64,
+    /// PageRank ranking.
+    pub pagerank: RankedCentrality,
+    /// Largest eigenvalue λ of the area graph.
+    pub eigenvalue: f64,
+    /// Eigenvector centrality ranking.
+    pub eigenvector: RankedCentrality,
+    /// Katz attenuation used.
+    pub katz_alpha: f64,
+    /// Katz centrality ranking.
+    pub katz: RankedCentrality,
+}
+
+impl AreaCentrality {
+    /// Runs all three centralities on `areas`, keeping the top `n` of each.
+    pub fn of(areas: &AreaGraph, options: &CentralityOptions, n: usize) -> Self {
+        let settings = options.iteration;
+        let personalization: Vec<f64> = areas
+            .node_indices()
+            .map(|node| if options.personalize.contains(&areas[node]) { 1.0 } else { 0.0 })
+            .collect();
+        let pagerank = pagerank(areas, options.damping, Some(&personalization), settings);
+        let eigenvector = eigenvector_centrality(areas, settings);
+        let eigenvalue = largest_eigenvalue(areas, &eigenvector);
+        let default_alpha = if eigenvalue > 0.0 { 0.9 / eigenvalue } else { 0.1 };
+        let katz_alpha = options.katz_alpha.unwrap_or(default_alpha);
+        let katz = katz_centrality(areas, katz_alpha, 1.0, settings);
+        AreaCentrality {
+            damping: options.damping,
+            pagerank: RankedCentrality::of(areas, &pagerank, n),
+            eigenvalue,
+            eigenvector: RankedCentrality::of(areas, &eigenvector, n),
+            katz_alpha,
+            katz: RankedCentrality::of(areas, &katz, n),
+        }
+    }
+}
+
+/// Local clustering of one node, a row of `clustering.csv`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeClustering {
+    /// Time bucket of the node.
+    pub period: String,
+    /// Area of the node.
+    pub area: String,
+    /// Degree of the node.
+    pub degree: usize,
+    /// Triangles through the node.
+    pub triangles: usize,
+    /// Local clustering coefficient.
+    pub clustering: f64,
+}
+
+/// Small-world diagnostics of the (bucket, area) graph.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClusteringSummary {
+    /// Mean local clustering over all nodes.
+    pub average_clustering: f64,
+    /// 3 × triangles / connected triples.
+    pub transitivity: f64,
+    /// Triangles in the graph.
+    pub triangles: usize,
+    /// Per-node values, exported to `clustering.csv` rather than `metrics.json`.
+    #[serde(skip)]
+    pub nodes: Vec<NodeClustering>,
+}
+
+/// One Louvain community of the area graph.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Community {
+    /// Community id, from 0 by decreasing size.
+    pub id: usize,
+    /// Number of areas.
+    pub size: usize,
+    /// Area names, sorted.
+    pub areas: Vec<String>,
+    /// Co-occurrence weight on edges inside the community.
+    pub internal_weight: usize,
+    /// Summed strength of its areas, internal edges counted from both ends.
+    pub strength: usize,
+}
+
+/// Louvain partition of the area co-occurrence graph, written as `communities.json`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AreaCommunities {
+    /// Resolution the partition was found at.
+    pub resolution: f64,
+    /// Modularity of the partition at `resolution`.
+    pub modularity: f64,
+    /// Aggregation levels Louvain ran.
+    pub levels: usize,
+    /// Communities, largest first.
+    pub communities: Vec<Community>,
+}
+
+impl AreaCommunities {
+    /// Partitions `areas` with Louvain at `resolution`.
+    pub fn of(areas: &AreaGraph, resolution: f64) -> Self {
+        let partition = louvain(areas, resolution);
+        let mut communities: Vec<Community> = partition
+            .communities()
+            .into_iter()
+            .enumerate()
+            .map(|(id, members)| Community {
+                id,
+                size: members.len(),
+                areas: members.iter().map(|&node| areas[node].clone()).sorted().collect(),
+                internal_weight: 0,
+                strength: 0,
+            })
+            .collect();
+        for e in areas.edge_references() {
+            let a = partition.membership[e.source().index()];
+            let b = partition.membership[e.target().index()];
+            communities[a].strength += *e.weight();
+            communities[b].strength += *e.weight();
+            if a == b {
+                communities[a].internal_weight += *e.weight();
+            }
+        }
+        AreaCommunities {
+            resolution,
+            modularity: partition.modularity,
+            levels: partition.levels,
+            communities,
+        }
+    }
+}
+
+/// Label-propagation communities of the (bucket, area) graph across seeds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PropagationSummary {
+    /// Seed of the reported run.
+    pub seed: u64,
+    /// Runs compared.
+    pub runs: usize,
+    /// Communities, modularity and sweeps of the run with `seed`.
+    pub communities: usize,
+    /// Modularity of the reported run.
+    pub modularity: f64,
+    /// Sweeps of the reported run.
+    pub sweeps: usize,
+    /// Whether the reported run stopped before `max_sweeps`.
+    pub converged: bool,
+    /// Share of nodes in the largest community.
+    pub largest_share: f64,
+    /// (community size, number of communities of that size), by size.
+    pub size_distribution: Vec<(usize, usize)>,
+    /// Community count of every run, in seed order.
+    pub community_counts: Vec<usize>,
+    /// Normalized mutual information over all pairs of runs (1 = identical).
+    pub mean_nmi: f64,
+    /// Lowest normalized mutual information between two runs.
+    pub min_nmi: f64,
+}
+
+impl PropagationSummary {
+    /// Runs label propagation `options.runs` times and compares the partitions.
+    pub fn of<G: Topology>(graph: &G, options: &PropagationOptions) -> Self {
+        let runs: Vec<_> = (0..options.runs.max(1) as u64)
+            .map(|i| label_propagation(graph, options.seed + i, options.max_sweeps))
+            .collect();
+        let scores: Vec<f64> = runs
+            .iter()
+            .tuple_combinations()
+            .map(|(a, b)| {
+                normalized_mutual_information(&a.partition.membership, &b.partition.membership)
+            })
+            .collect();
+        let first = &runs[0];
+        let communities = first.partition.communities();
+        let largest = communities.first().map_or(0, Vec::len);
+        let size_distribution = communities
+            .iter()
+            .map(Vec::len)
+            .counts()
+            .into_iter()
+            .sorted()
+            .collect();
+        PropagationSummary {
+            seed: options.seed,
+            runs: runs.len(),
+            communities: communities.len(),
+            modularity: first.partition.modularity,
+            sweeps: first.sweeps,
+            converged: first.converged,
+            largest_share: largest as f64 / graph.node_count().max(1) as f64,
+            size_distribution,
+            community_counts: runs.iter().map(|r| r.partition.community_count()).collect(),
+            mean_nmi: if scores.is_empty() {
+                1.0
+            } else {
+                scores.iter().sum::<f64>() / scores.len() as f64
+            },
+            min_nmi: scores.iter().copied().fold(1.0, f64::min),
+        }
+    }
+}
+
+/// One connected component, a row of `components.csv`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentRow {
+    /// Component id.
+    pub component: usize,
+    /// Number of nodes.
+    pub size: usize,
+    /// Earliest time bucket in the component.
+    pub first_period: String,
+    /// Latest time bucket in the component.
+    pub last_period: String,
+    /// Distinct time buckets in the component.
+    pub periods: usize,
+    /// Distinct areas in the component.
+    pub area_count: usize,
+    /// Area names joined with `;`.
+    pub areas: String,
+}
+
+/// Component of one node, a row of `component_membership.csv`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeComponent {
+    /// Time bucket of the node.
+    pub period: String,
+    /// Area of the node.
+    pub area: String,
+    /// Component id, as in `components.csv`.
+    pub component: usize,
+}
+
+/// Connected components of the graph, summarized in `metrics.json`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentSummary {
+    /// Number of components.
+    pub count: usize,
+    /// Nodes in the largest component.
+    pub largest_size: usize,
+    /// Share of all nodes in the largest component.
+    pub largest_share: f64,
+    /// (component size, number of components of that size), by size.
+    pub size_distribution: Vec<(usize, usize)>,
+    /// Per-component spans, exported to `components.csv`. Ids follow
+    /// `component_membership`, as in `eccentricity.csv`.
+    #[serde(skip)]
+    pub components: Vec<ComponentRow>,
+    /// Component id of every node, exported to `component_membership.csv`.
+    #[serde(skip)]
+    pub membership: Vec<NodeComponent>,
+}
+
+impl ComponentSummary {
+    /// Finds the components of `graph` and their spans.
+    pub fn of<G: Topology<Node = (Period, String)>>(graph: &G) -> Self {
+        let membership = component_membership(graph);
+        let spans = component_spans(graph, &membership);
+        let largest_size = spans.iter().map(|s| s.size).max().unwrap_or(0);
+        ComponentSummary {
+            count: spans.len(),
+            largest_size,
+            largest_share: largest_size as f64 / graph.node_count().max(1) as f64,
+            size_distribution: spans
+                .iter()
+                .map(|s| s.size)
+                .counts()
+                .into_iter()
+                .sorted()
+                .collect(),
+            components: spans
+                .iter()
+                .enumerate()
+                .map(|(component, span)| ComponentRow {
+                    component,
+                    size: span.size,
+                    first_period: span.first.to_string(),
+                    last_period: span.last.to_string(),
+                    periods: span.periods,
+                    area_count: span.areas.len(),
+                    areas: span.areas.iter().join(";"),
+                })
+                .collect(),
+            membership: graph
+                .nodes()
+                .map(|node| {
+                    let (period, area) = graph.node(node);
+                    NodeComponent {
+                        period: period.to_string(),
+                        area: area.clone(),
+                        component: membership[node.index()],
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Core number of one node, a row of `core_numbers.csv`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeCore {
+    /// Time bucket of the node.
+    pub period: String,
+    /// Area of the node.
+    pub area: String,
+    /// Core number: the largest `k` whose k-core holds the node.
+    pub core: usize,
+}
+
+/// Eccentricity of one node, a row of `eccentricity.csv`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeEccentricity {
+    /// Time bucket of the node.
+    pub period: String,
+    /// Area of the node.
+    pub area: String,
+    /// Component id, as in `components.csv`.
+    pub component: usize,
+    /// Largest distance to a node of the same component.
+    pub eccentricity: usize,
+    /// Whether the eccentricity equals the component's radius.
+    pub center: bool,
+    /// Whether the eccentricity equals the component's diameter.
+    pub periphery: bool,
+}
+
+/// Diameter and radius of one component, a row of `component_shapes.csv`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentShapeRow {
+    /// Component id.
+    pub component: usize,
+    /// Number of nodes.
+    pub size: usize,
+    /// Largest eccentricity in the component.
+    pub diameter: usize,
+    /// Smallest eccentricity in the component.
+    pub radius: usize,
+    /// Nodes whose eccentricity equals the radius.
+    pub center_size: usize,
+    /// Nodes whose eccentricity equals the diameter.
+    pub periphery_size: usize,
+}
+
+/// The largest component's shape, with its center and periphery labelled.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LargestShape {
+    /// Component id.
+    pub component: usize,
+    /// Number of nodes.
+    pub size: usize,
+    /// Largest eccentricity in the component.
+    pub diameter: usize,
+    /// Smallest eccentricity in the component.
+    pub radius: usize,
+    /// (period, area) of the center nodes.
+    pub center: Vec<(String, String)>,
+    /// (period, area) of the periphery nodes.
+    pub periphery: Vec<(String, String)>,
+}
+
+/// Eccentricity-based measures from the all-pairs pass.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ShapeSummary {
+    /// Largest diameter over all components.
+    pub diameter: usize,
+    /// Shape of the largest component, ties to the lowest id.
+    pub largest_component: LargestShape,
+    /// Per-component shapes, exported to `component_shapes.csv`.
+    #[serde(skip)]
+    pub components: Vec<ComponentShapeRow>,
+    /// Per-node eccentricity, exported to `eccentricity.csv`.
+    #[serde(skip)]
+    pub nodes: Vec<NodeEccentricity>,
+}
+
+impl ShapeSummary {
+    /// Groups per-node `eccentricity` by connected component of `graph`.
+    pub fn of<G: Topology<Node = (Period, String)>>(graph: &G, eccentricity: &[usize]) -> Self {
+        let label = |node: NodeIndex| {
+            let (period, area) = graph.node(node);
+            (period.to_string(), area.clone())
+        };
+        let membership = component_membership(graph);
+        let shapes = component_shapes(&membership, eccentricity);
+        // Largest by size, ties to the lowest component id.
+        let largest = shapes
+            .iter()
+            .enumerate()
+            .max_by(|(a, x), (b, y)| x.size.cmp(&y.size).then(b.cmp(a)))
+            .map_or(0, |(id, _)| id);
+        let nodes = graph
+            .nodes()
+            .map(|node| {
+                let (period, area) = label(node);
+                let component = membership[node.index()];
+                let e = eccentricity[node.index()];
+                NodeEccentricity {
+                    period,
+                    area,
+                    component,
+                    eccentricity: e,
+                    center: e == shapes[component].radius,
+                    periphery: e == shapes[component].diameter,
+                }
+            })
+            .collect();
+        let components = shapes
+            .iter()
+            .enumerate()
+            .map(|(component, shape)| ComponentShapeRow {
+                component,
+                size: shape.size,
+                diameter: shape.diameter,
+                radius: shape.radius,
+                center_size: shape.center.len(),
+                periphery_size: shape.periphery.len(),
+            })
+            .collect();
+        let shape = shapes.get(largest).cloned().unwrap_or_default();
+        ShapeSummary {
+            diameter: shapes.iter().map(|s| s.diameter).max().unwrap_or(0),
+            largest_component: LargestShape {
+                component: largest,
+                size: shape.size,
+                diameter: shape.diameter,
+                radius: shape.radius,
+                center: shape.center.iter().map(|&n| label(n)).collect(),
+                periphery: shape.periphery.iter().map(|&n| label(n)).collect(),
+            },
+            components,
+            nodes,
+        }
+    }
+}
+
+/// Results of the selected metrics over one graph.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Time bucket of the graph's nodes; `None` for an empty graph.
+    pub bucket: Option<TimeBucket>,
+    /// Number of nodes.
+    pub nodes: usize,
+    /// Number of edges.
+    pub edges: usize,
+    /// Length of the top-N rankings.
+    pub top_n: usize,
+    /// (degree, node count), by degree.
+    pub degree_distribution: Option<Vec<(usize, usize)>>,
+    /// Degree assortativity; `Some(None)` when it is undefined for the graph.
+    pub assortativity: Option<Option<f64>>,
+    /// (degree, average neighbour degree, nodes of that degree), by degree.
+    pub neighbor_degree: Option<Vec<(usize, f64, usize)>>,
+    /// Model fits of the degree tail; `Some(None)` when no tail is large enough.
+    pub degree_fit: Option<Option<DegreeFits>>,
+    /// (core number, nodes in that k-shell), by core number.
+    pub core_shells: Option<Vec<(usize, usize)>>,
+    /// Core number of every node, exported as `core_numbers.csv`.
+    pub core_numbers: Option<Vec<NodeCore>>,
+    /// Mean distance over all reached pairs.
+    pub avg_path: Option<f64>,
+    /// `distance_histogram[d]` = ordered pairs at distance `d`, computed with `avg_path`.
+    pub distance_histogram: Option<Vec<u64>>,
+    /// Highest closeness scores as ((period, area), score).
+    pub top_closeness: Option<Vec<((String, String), f64)>>,
+    /// Closeness variant behind `top_closeness` or `closeness_estimates`.
+    pub closeness: Option<Closeness>,
+    /// Set when the path metrics were estimated from a pivot sample.
+    pub path_sample: Option<PathSample>,
+    /// Sampled per-node closeness, unranked; exported as `closeness_estimates.csv`.
+    pub closeness_estimates: Option<Vec<ClosenessEstimate>>,
+    /// Number of connected components.
+    pub components: Option<usize>,
+    /// Component sizes and spans.
+    pub component_summary: Option<ComponentSummary>,
+    /// Eccentricity-based measures.
+    pub shape: Option<ShapeSummary>,
+    /// Clustering and transitivity.
+    pub clustering: Option<ClusteringSummary>,
+    /// Highest node betweenness as ((period, area), score).
+    pub top_betweenness: Option<Vec<((String, String), f64)>>,
+    /// Highest edge betweenness.
+    pub top_edge_betweenness: Option<Vec<EdgeScore>>,
+    /// Source pivots behind the betweenness scores when sampled.
+    pub betweenness_pivots: Option<usize>,
+    /// Area co-occurrence graph, for `Metric::AreaCooccurrence`.
+    pub area_graph: Option<AreaGraph>,
+    /// Area centrality rankings.
+    pub area_centrality: Option<AreaCentrality>,
+    /// Louvain communities of the area graph.
+    pub area_communities: Option<AreaCommunities>,
+    /// Label-propagation communities and their stability.
+    pub label_propagation: Option<PropagationSummary>,
+    /// Day–area bipartite graph summary.
+    pub bipartite: Option<BipartiteSummary>,
+    /// Quality of the input file, attached by the caller that read it.
+    pub quality: Option<DataQuality>,
+}
+
+impl Report {
+    /// Runs every metric in `metrics` over `graph`, keeping the top `top_n` rankings.
+    pub fn compute<G: Topology<Node = (Period, String)> + Sync>(
+        graph: &G,
+        metrics: &[Metric],
+        top_n: usize,
+    ) -> Self {
+        let options = ReportOptions {
+            metrics: metrics.to_vec(),
+            top_n,
+            ..Default::default()
+        };
+        Self::compute_with(graph, &options)
+    }
+
+    /// Runs the metrics selected in `options` over `graph`.
+    ///
+    /// Average path length, closeness and eccentricity share a single parallel
+    /// all-pairs pass, or average path and closeness share a single pivot sample
+    /// when `options.path_mode` resolves to approximate and eccentricity is off.
+    pub fn compute_with<G: Topology<Node = (Period, String)> + Sync>(
+        graph: &G,
+        options: &ReportOptions,
+    ) -> Self {
+        let metrics = &options.metrics;
+        let top_n = options.top_n;
+        let mut report = Report {
+            bucket: graph.nodes().next().map(|node| graph.node(node).0.bucket),
+            nodes: graph.node_count(),
+            edges: graph.edge_count(),
+            top_n,
+            ..Default::default()
+        };
+        if metrics.contains(&Metric::Degree) || metrics.contains(&Metric::DegreeFit) {
+            let dist: Vec<_> = degree_distribution(graph)
+                .into_iter()
+                .sorted_by_key(|&(d, _)| d)
+                .collect();
+            if metrics.contains(&Metric::DegreeFit) {
+                report.degree_fit = Some(fit_degrees(&dist, options.fit_min_tail));
+            }
+            if metrics.contains(&Metric::Degree) {
+                report.degree_distribution = Some(dist);
+            }
+        }
+        if metrics.contains(&Metric::Assortativity) {
+            report.assortativity = Some(degree_assortativity(graph));
+            report.neighbor_degree = Some(avg_neighbor_degree(graph));
+        }
+        if metrics.contains(&Metric::KCore) {
+            let cores = core_decomposition(graph);
+            let rows = graph
+                .nodes()
+                .map(|node| {
+                    let (period, area) = graph.node(node);
+                    NodeCore {
+                        period: period.to_string(),
+                        area: area.clone(),
+                        core: cores.core[node.index()],
+                    }
+                })
+                .collect();
+            report.core_numbers = Some(rows);
+            report.core_shells = Some(cores.shells);
+        }
+        let wants_avg = metrics.contains(&Metric::AvgPath);
+        let wants_closeness = metrics.contains(&Metric::Closeness);
+        let wants_eccentricity = metrics.contains(&Metric::Eccentricity);
+        if wants_closeness {
+            report.closeness = Some(options.closeness);
+        }
+        // Eccentricity needs a BFS from every node, and that pass then serves
+        // average path and closeness exactly too.
+        let sampled = match options.path_mode.resolve(graph.node_count(), graph.edge_count()) {
+            PathMode::Approximate { samples, seed } if !wants_eccentricity => Some((samples, seed)),
+            _ => None,
+        };
+        if let Some((samples, seed)) = sampled.filter(|_| wants_avg || wants_closeness) {
+            let sample = sampled_pairs(graph, samples, seed, options.threads);
+            let mut summary = PathSample { pivots: sample.pivots.len(), seed, avg_path: None };
+            if wants_avg {
+                report.avg_path = sample.avg_path.map(|e| e.value);
+                summary.avg_path = sample.avg_path;
+            }
+            // Per-node intervals from a few hundred pivots overlap too much to
+            // rank; the top of such a ranking is mostly sampling noise.
+            if wants_closeness {
+                let estimates = sample.closeness(options.closeness);
+                let rows = graph
+                    .nodes()
+                    .zip(estimates)
+                    .map(|(node, estimate)| {
+                        let (period, area) = graph.node(node);
+                        ClosenessEstimate {
+                            period: period.to_string(),
+                            area: area.clone(),
+                            closeness: estimate.value,
+                            lower: estimate.lower,
+                            upper: estimate.upper,
+                        }
+                    })
+                    .collect();
+                report.closeness_estimates = Some(rows);
+            }
+            report.path_sample = Some(summary);
+        } else if sampled.is_none() && (wants_avg || wants_closeness || wants_eccentricity) {
+            let paths = all_pairs(graph, options.threads);
+            if wants_avg {
+                report.avg_path = Some(paths.avg_path());
+                report.distance_histogram = Some(paths.histogram.clone());
+            }
+            if wants_closeness {
+                let scores = paths.closeness(options.closeness);
+                report.top_closeness = Some(top_scores(graph, &scores, top_n));
+            }
+            if wants_eccentricity {
+                report.shape = Some(ShapeSummary::of(graph, &paths.eccentricity()));
+            }
+        }
+        if metrics.contains(&Metric::Components) {
+            let summary = ComponentSummary::of(graph);
+            report.components = Some(summary.count);
+            report.component_summary = Some(summary);
+        }
+        if metrics.contains(&Metric::Clustering) {
+            let stats = clustering(graph);
+            let nodes = graph
+                .nodes()
+                .map(|node| {
+                    let (period, area) = graph.node(node);
+                    NodeClustering {
+                        period: period.to_string(),
+                        area: area.clone(),
+                        degree: graph.degree(node),
+                        triangles: stats.triangles[node.index()],
+                        clustering: stats.local[node.index()],
+                    }
+                })
+                .collect();
+            report.clustering = Some(ClusteringSummary {
+                average_clustering: stats.average,
+                transitivity: stats.transitivity,
+                triangles: stats.triangle_count,
+                nodes,
+            });
+        }
+        if metrics.contains(&Metric::LabelPropagation) {
+            report.label_propagation = Some(PropagationSummary::of(graph, &options.propagation));
+        }
+        if metrics.contains(&Metric::Betweenness) {
+            let scores = match options.path_mode.resolve(graph.node_count(), graph.edge_count()) {
+                PathMode::Approximate { samples, seed } => {
+                    sampled_betweenness(graph, samples, seed, options.threads)
+                }
+                _ => betweenness(graph, options.threads),
+            };
+            report.top_betweenness = Some(top_scores(graph, &scores.nodes, top_n));
+            report.top_edge_betweenness = Some(top_edges(graph, &scores.edges, top_n));
+            report.betweenness_pivots = scores.pivots;
+        }
+        let wants_centrality = metrics.contains(&Metric::AreaCentrality);
+        let wants_communities = metrics.contains(&Metric::Communities);
+        if metrics.contains(&Metric::AreaCooccurrence) || wants_centrality || wants_communities {
+            let areas = area_projection(graph);
+            if wants_communities {
+                report.area_communities = Some(AreaCommunities::of(&areas, options.resolution));
+            }
+            if wants_centrality {
+                let centrality = AreaCentrality::of(&areas, &options.centrality, top_n);
+                report.area_centrality = Some(centrality);
+            }
+            if metrics.contains(&Metric::AreaCooccurrence) {
+                report.area_graph = Some(areas);
+            }
+        }
+        if metrics.contains(&Metric::Bipartite) {
+            // The nodes carry every (bucket, area) pair; `from_entries` skips the graph.
+            report.bipartite = Some(BipartiteSummary::of(&bipartite_projection(graph)));
+        }
+        report
+    }
+
+    /// Runs `options.metrics` on entries read from `path` with `graph_options`.
+    ///
+    /// The bipartite metric is computed on the day–area graph built straight
+    /// from `entries`; the others on the (bucket, area) graph, materialized
+    /// unless `implicit`. When bipartite is the only metric the graph is kept
+    /// implicit, so its clique edges are never built just for the node and
+    /// edge counts.
+    ///
+    /// As in `build_area_graph_with`, a nonzero `graph_options.temporal_lag` is
+    /// rejected when an area-level metric is requested, since the area graph
+    /// has no temporal edges. With `Metric::AreaCentrality`, personalization
+    /// names that match no area of `entries` are rejected too.
+    pub fn from_entries(
+        path: &Path,
+        entries: &[(Period, String)],
+        graph_options: &GraphOptions,
+        options: &ReportOptions,
+        implicit: bool,
+    ) -> Result<Self, GraphError> {
+        let area_metrics = [Metric::AreaCooccurrence, Metric::AreaCentrality, Metric::Communities];
+        if let Some(metric) = options.metrics.iter().find(|m| area_metrics.contains(m)) {
+            if graph_options.temporal_lag > 0 {
+                return Err(GraphError::UnsupportedOption {
+                    path: path.to_path_buf(),
+                    option: "temporal_lag".to_string(),
+                    reason: format!(
+                        "{} runs on the area graph, which has no temporal edges",
+                        metric
+                    ),
+                });
+            }
+        }
+        if options.metrics.contains(&Metric::AreaCentrality) {
+            let areas: BTreeSet<&str> = entries.iter().map(|(_, area)| area.as_str()).collect();
+            let unknown = options.centrality.unknown_areas(areas.iter().copied());
+            if !unknown.is_empty() {
+                return Err(GraphError::UnknownAreas {
+                    path: path.to_path_buf(),
+                    option: "personalize".to_string(),
+                    unknown,
+                    available: areas.iter().map(|area| area.to_string()).collect(),
+                });
+            }
+        }
+        if let (true, Some(alpha)) =
+            (options.metrics.contains(&Metric::AreaCentrality), options.centrality.katz_alpha)
+        {
+            let areas = project_areas(entries.iter().map(|(p, area)| (*p, area.as_str())));
+            let eigenvector = eigenvector_centrality(&areas, options.centrality.iteration);
+            let eigenvalue = largest_eigenvalue(&areas, &eigenvector);
+            if eigenvalue > 0.0 && alpha >= 1.0 / eigenvalue {
+                return Err(GraphError::UnsupportedOption {
+                    path: path.to_path_buf(),
+                    option: "katz_alpha".to_string(),
+                    reason: format!(
+                        "Katz centrality diverges unless alpha is below 1 / λ = {:.3e} \
+                         (λ = {:.1}, the largest eigenvalue of the area graph), got {}",
+                        1.0 / eigenvalue,
+                        eigenvalue,
+                        alpha
+                    ),
+                });
+            }
+        }
+        let rest = ReportOptions {
+            metrics: options.metrics.iter().copied().filter(|&m| m != Metric::Bipartite).collect(),
+            ..options.clone()
+        };
+        let mut report = if implicit || rest.metrics.is_empty() {
+            Report::compute_with(&CliqueGraph::from_entries(entries, graph_options), &rest)
+        } else {
+            Report::compute_with(&graph_from_entries(entries, graph_options), &rest)
+        };
+        if options.metrics.contains(&Metric::Bipartite) {
+            let graph = bipartite_from_pairs(entries.iter().map(|(p, area)| (*p, area.as_str())));
+            report.bipartite = Some(BipartiteSummary::of(&graph));
+        }
+        Ok(report)
+    }
+
+    /// Attaches the data-quality summary of the input, exported as `data_quality.json`.
+    pub fn with_quality(mut self, quality: DataQuality) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    /// Prints the report in the human-readable layout.
+    pub fn print_text(&self) {
+        if let Some(quality) = &self.quality {
+            println!(
+                "Rows read: {}, kept: {}, skipped: {}, duplicates: {}",
+                quality.rows_read,
+                quality.rows_kept,
+                quality.skipped_rows(),
+                quality.duplicate_rows
+            );
+            if let (Some(first), Some(last)) = (quality.first_day, quality.last_day) {
+                println!(
+                    "Date range: {} to {} ({} days without data)",
+                    first,
+                    last,
+                    quality.days_without_data.len()
+                );
+            }
+            if !quality.unknown_areas.is_empty() {
+                println!(
+                    "Unknown areas: {}",
+                    quality.unknown_areas.keys().join(", ")
+                );
+            }
+        }
+        println!("Graph built: {} nodes, {} edges", self.nodes, self.edges);
+        if let Some(bucket) = self.bucket {
+            println!("Time bucket: {}", bucket);
+        }
+        if let Some(dist) = &self.degree_distribution {
+            println!("Degree distribution:");
+            for (d, cnt) in dist {
+                println!("  {} → {}", d, cnt);
+            }
+        }
+        if let Some(assortativity) = self.assortativity {
+            match assortativity {
+                Some(r) => println!("Degree assortativity: {:.4}", r),
+                None => println!("Degree assortativity: undefined (one degree at every edge end)"),
+            }
+        }
+        if let Some(fit) = &self.degree_fit {
+            match fit {
+                Some(fit) => {
+                    println!("Degree fits (x_min {}, {} nodes in the tail):", fit.x_min, fit.tail);
+                    let power = &fit.power_law;
+                    println!("  power law    α = {:.4}, KS {:.4}", power.alpha, power.ks);
+                    let exp = &fit.exponential;
+                    println!("  exponential  λ = {:.4}, KS {:.4}", exp.lambda, exp.ks);
+                    let log = &fit.log_normal;
+                    println!(
+                        "  log-normal   μ = {:.4}, σ = {:.4}, KS {:.4}",
+                        log.mu, log.sigma, log.ks
+                    );
+                    for c in &fit.comparisons {
+                        println!(
+                            "  {} vs {}: R = {:.2}, p = {:.4} → {}",
+                            c.first,
+                            c.second,
+                            c.log_ratio,
+                            c.p_value,
+                            c.favored.unwrap_or("inconclusive")
+                        );
+                    }
+                }
+                None => println!("Degree fits: no degree tail with enough nodes"),
+            }
+        }
+        if let Some(curve) = &self.neighbor_degree {
+            println!("Average neighbor degree by degree:");
+            for (k, knn, _) in curve {
+                println!("  {} → {:.2}", k, knn);
+            }
+        }
+        if let Some(shells) = &self.core_shells {
+            let max_core = shells.last().map_or(0, |&(k, _)| k);
+            println!("k-core: max core {}, shell sizes:", max_core);
+            for (k, cnt) in shells {
+                println!("  {} → {}", k, cnt);
+            }
+        }
+        if let Some(sample) = &self.path_sample {
+            println!("Path metrics estimated from {} pivots (seed {})", sample.pivots, sample.seed);
+            if sample.pivots == 0 {
+                println!("  no pivots sampled: average path and closeness are unknown");
+            }
+        }
+        if let Some(avg) = self.avg_path {
+            match self.path_sample.as_ref().and_then(|s| s.avg_path) {
+                Some(ci) => println!(
+                    "Avg shortest-path length: {:.3} (95% CI {:.3}–{:.3})",
+                    avg, ci.lower, ci.upper
+                ),
+                None => println!("Avg shortest-path length: {:.3}", avg),
+            }
+        }
+        if let Some(top) = &self.top_closeness {
+            match self.closeness {
+                Some(kind) => println!("Top {} closeness centrality ({}):", self.top_n, kind),
+                None => println!("Top {} closeness centrality:", self.top_n),
+            }
+            for ((day, area), score) in top {
+                println!("  {} | {} → {:.4}", day, area, score);
+            }
+        }
+        if let (Some(estimates), Some(kind)) = (&self.closeness_estimates, self.closeness) {
+            println!(
+                "Closeness ({}): sampled for {} nodes, not ranked (closeness_estimates.csv)",
+                kind,
+                estimates.len()
+            );
+        }
+        if let Some(comps) = self.components {
+            println!("Connected components: {}", comps);
+        }
+        if let Some(summary) = &self.component_summary {
+            println!(
+                "Largest component: {} nodes ({:.1}% of all nodes)",
+                summary.largest_size,
+                100.0 * summary.largest_share
+            );
+        }
+        if let Some(shape) = &self.shape {
+            let largest = &shape.largest_component;
+            println!(
+                "Largest component #{} ({} nodes): diameter {}, radius {}, \
+                 {} center and {} periphery nodes",
+                largest.component,
+                largest.size,
+                largest.diameter,
+                largest.radius,
+                largest.center.len(),
+                largest.periphery.len()
+            );
+            println!("Graph diameter (over all components): {}", shape.diameter);
+        }
+        if let Some(stats) = &self.clustering {
+            println!(
+                "Clustering: average {:.4}, transitivity {:.4}, {} triangles",
+                stats.average_clustering, stats.transitivity, stats.triangles
+            );
+        }
+        if let Some(lp) = &self.label_propagation {
+            println!(
+                "Label propagation (seed {}): {} communities, modularity {:.4}, largest {:.1}% \
+                 of nodes, {} sweeps{}",
+                lp.seed,
+                lp.communities,
+                lp.modularity,
+                100.0 * lp.largest_share,
+                lp.sweeps,
+                if lp.converged { "" } else { " (not converged)" }
+            );
+            println!("  Community sizes (seed {}):", lp.seed);
+            for (size, cnt) in &lp.size_distribution {
+                println!("    {} → {}", size, cnt);
+            }
+            println!(
+                "  Stability over {} seeds: NMI mean {:.4}, min {:.4}; communities per seed: {}",
+                lp.runs,
+                lp.mean_nmi,
+                lp.min_nmi,
+                lp.community_counts.iter().join(", ")
+            );
+        }
+        if let Some(top) = &self.top_betweenness {
+            match self.betweenness_pivots {
+                Some(pivots) => println!(
+                    "Top {} betweenness centrality (estimated from {} pivots):",
+                    self.top_n, pivots
+                ),
+                None => println!("Top {} betweenness centrality:", self.top_n),
+            }
+            for ((day, area), score) in top {
+                println!("  {} | {} → {:.1}", day, area, score);
+            }
+        }
+        if let Some(top) = &self.top_edge_betweenness {
+            println!("Top {} edge betweenness:", self.top_n);
+            for (((day_a, area_a), (day_b, area_b)), score) in top {
+                println!("  {} | {} — {} | {} → {:.1}", day_a, area_a, day_b, area_b, score);
+            }
+        }
+        if let Some(areas) = &self.area_graph {
+            println!(
+                "Area co-occurrence graph: {} areas, {} weighted edges",
+                areas.node_count(),
+                areas.edge_count()
+            );
+            println!("Top {} co-occurring area pairs:", self.top_n);
+            let unit = self.bucket.unwrap_or_default();
+            for ((a, b), shared) in top_cooccurrences(areas, self.top_n) {
+                println!("  {} & {} → {} {}s", a, b, shared, unit);
+            }
+        }
+        if let Some(centrality) = &self.area_centrality {
+            let ranked = [
+                (format!("PageRank (damping {})", centrality.damping), &centrality.pagerank),
+                (
+                    format!("eigenvector (λ = {:.1})", centrality.eigenvalue),
+                    &centrality.eigenvector,
+                ),
+                (format!("Katz (alpha = {:.3e})", centrality.katz_alpha), &centrality.katz),
+            ];
+            for (name, ranking) in ranked {
+                let status = if ranking.converged { "converged" } else { "not converged" };
+                println!(
+                    "Top {} areas by {}, {} iterations, {}:",
+                    self.top_n, name, ranking.iterations, status
+                );
+                for (area, score) in &ranking.top {
+                    println!("  {} → {:.4}", area, score);
+                }
+            }
+        }
+        if let Some(blocs) = &self.area_communities {
+            println!(
+                "Area communities: {} (modularity {:.4}, resolution {})",
+                blocs.communities.len(),
+                blocs.modularity,
+                blocs.resolution
+            );
+            for community in &blocs.communities {
+                println!("  #{}: {}", community.id, community.areas.join(", "));
+            }
+        }
+        if let Some(bip) = &self.bipartite {
+            println!(
+                "Bipartite graph: {} days, {} areas, {} edges",
+                bip.day_nodes, bip.area_nodes, bip.edges
+            );
+            bip.print_text();
+        }
+    }
+
+    /// Returns the report as the JSON object written to `metrics.json`.
+    pub fn to_json(&self) -> Value {
+        let mut map = Map::new();
+        if let Some(bucket) = self.bucket {
+            map.insert("bucket".into(), json!(bucket));
+        }
+        map.insert("nodes".into(), json!(self.nodes));
+        map.insert("edges".into(), json!(self.edges));
+        if let Some(dist) = &self.degree_distribution {
+            map.insert("degree_distribution".into(), json!(dist));
+        }
+        if let Some(assortativity) = self.assortativity {
+            map.insert("degree_assortativity".into(), json!(assortativity));
+        }
+        if let Some(fit) = &self.degree_fit {
+            map.insert("degree_fit".into(), json!(fit));
+        }
+        if let Some(curve) = &self.neighbor_degree {
+            map.insert("avg_neighbor_degree".into(), json!(curve));
+        }
+        if let Some(shells) = &self.core_shells {
+            map.insert("max_core".into(), json!(shells.last().map_or(0, |&(k, _)| k)));
+            map.insert("k_shells".into(), json!(shells));
+        }
+        if let Some(avg) = self.avg_path {
+            map.insert("avg_path".into(), json!(avg));
+        }
+        if let Some(histogram) = &self.distance_histogram {
+            map.insert("distance_histogram".into(), json!(histogram));
+        }
+        if let Some(kind) = self.closeness {
+            map.insert("closeness".into(), json!(kind.to_string()));
+        }
+        if let Some(top) = &self.top_closeness {
+            map.insert(format!("top{}_closeness", self.top_n), json!(top));
+        }
+        if let Some(sample) = &self.path_sample {
+            map.insert("path_sample".into(), json!(sample));
+        }
+        if let Some(comps) = self.components {
+            map.insert("components".into(), json!(comps));
+        }
+        if let Some(summary) = &self.component_summary {
+            map.insert("component_summary".into(), json!(summary));
+        }
+        if let Some(shape) = &self.shape {
+            map.insert("eccentricity".into(), json!(shape));
+        }
+        if let Some(stats) = &self.clustering {
+            map.insert("clustering".into(), json!(stats));
+        }
+        if let Some(lp) = &self.label_propagation {
+            map.insert("label_propagation".into(), json!(lp));
+        }
+        if let Some(top) = &self.top_betweenness {
+            map.insert(format!("top{}_betweenness", self.top_n), json!(top));
+        }
+        if let Some(top) = &self.top_edge_betweenness {
+            map.insert(format!("top{}_edge_betweenness", self.top_n), json!(top));
+        }
+        if let Some(pivots) = self.betweenness_pivots {
+            map.insert("betweenness_pivots".into(), json!(pivots));
+        }
+        if let Some(areas) = &self.area_graph {
+            map.insert(
+                "area_graph".into(),
+                json!({
+                    "nodes": areas.node_count(),
+                    "edges": areas.edge_count(),
+                    "top_pairs": top_cooccurrences(areas, self.top_n),
+                    "strengths": area_strengths(areas),
+                }),
+            );
+        }
+        if let Some(centrality) = &self.area_centrality {
+            map.insert("area_centrality".into(), json!(centrality));
+        }
+        if let Some(blocs) = &self.area_communities {
+            map.insert(
+                "area_communities".into(),
+                json!({
+                    "count": blocs.communities.len(),
+                    "modularity": blocs.modularity,
+                    "resolution": blocs.resolution,
+                    "sizes": blocs.communities.iter().map(|c| c.size).collect_vec(),
+                }),
+            );
+        }
+        if let Some(bip) = &self.bipartite {
+            map.insert("bipartite".into(), json!(bip));
+        }
+        Value::Object(map)
+    }
+
+    /// Writes `metrics.json` and, if present, `degree_counts.csv`, `neighbor_degree.csv`,
+    /// `core_counts.csv`, `core_numbers.csv`, `area_cooccurrence.csv`, `components.csv`,
+    /// `component_membership.csv`, `closeness_estimates.csv`, `eccentricity.csv`,
+    /// `component_shapes.csv`, `clustering.csv`, `area_communities.csv`, `communities.json`
+    /// and `data_quality.json` into `out_dir`.
+    ///
+    /// Returns the paths of the files written, in write order.
+    pub fn write<P: AsRef<Path>>(&self, out_dir: P) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        let out_dir = out_dir.as_ref();
+        fs::create_dir_all(out_dir)?;
+        let mut written = Vec::new();
+
+        let metrics_path = out_dir.join("metrics.json");
+        fs::write(&metrics_path, serde_json::to_string_pretty(&self.to_json())?)?;
+        written.push(metrics_path);
+
+        if let Some(dist) = &self.degree_distribution {
+            let degree_path = out_dir.join("degree_counts.csv");
+            let mut wtr = Writer::from_path(&degree_path)?;
+            wtr.write_record(["degree", "count"])?;
+            for (d, cnt) in dist {
+                wtr.write_record(&[d.to_string(), cnt.to_string()])?;
+            }
+            wtr.flush()?;
+            written.push(degree_path);
+        }
+
+        if let Some(curve) = &self.neighbor_degree {
+            let curve_path = out_dir.join("neighbor_degree.csv");
+            let mut wtr = Writer::from_path(&curve_path)?;
+            wtr.write_record(["degree", "avg_neighbor_degree", "count"])?;
+            for (k, knn, cnt) in curve {
+                wtr.write_record(&[k.to_string(), knn.to_string(), cnt.to_string()])?;
+            }
+            wtr.flush()?;
+            written.push(curve_path);
+        }
+
+        if let Some(shells) = &self.core_shells {
+            let core_path = out_dir.join("core_counts.csv");
+            let mut wtr = Writer::from_path(&core_path)?;
+            wtr.write_record(["core", "count"])?;
+            for (k, cnt) in shells {
+                wtr.write_record(&[k.to_string(), cnt.to_string()])?;
+            }
+            wtr.flush()?;
+            written.push(core_path);
+        }
+
+        if let Some(cores) = &self.core_numbers {
+            let numbers_path = out_dir.join("core_numbers.csv");
+            let mut wtr = Writer::from_path(&numbers_path)?;
+            for row in cores {
+                wtr.serialize(row)?;
+            }
+            wtr.flush()?;
+            written.push(numbers_path);
+        }
+
+        if let Some(areas) = &self.area_graph {
+            let area_path = out_dir.join("area_cooccurrence.csv");
+            let mut wtr = Writer::from_path(&area_path)?;
+            let unit = format!("{}s", self.bucket.unwrap_or_default());
+            wtr.write_record(["area_a", "area_b", unit.as_str()])?;
+            for e in areas.edge_references() {
+                wtr.write_record(&[
+                    areas[e.source()].clone(),
+                    areas[e.target()].clone(),
+                    e.weight().to_string(),
+                ])?;
+            }
+            wtr.flush()?;
+            written.push(area_path);
+        }
+
+        if let Some(summary) = &self.component_summary {
+            let components_path = out_dir.join("components.csv");
+            let mut wtr = Writer::from_path(&components_path)?;
+            for row in &summary.components {
+                wtr.serialize(row)?;
+            }
+            wtr.flush()?;
+            written.push(components_path);
+
+            let membership_path = out_dir.join("component_membership.csv");
+            let mut wtr = Writer::from_path(&membership_path)?;
+            for row in &summary.membership {
+                wtr.serialize(row)?;
+            }
+            wtr.flush()?;
+            written.push(membership_path);
+        }
+
+        if let Some(estimates) = &self.closeness_estimates {
+            let estimates_path = out_dir.join("closeness_estimates.csv");
+            let mut wtr = Writer::from_path(&estimates_path)?;
+            for row in estimates {
+                wtr.serialize(row)?;
+            }
+            wtr.flush()?;
+            written.push(estimates_path);
+        }
+
+        if let Some(shape) = &self.shape {
+            let eccentricity_path = out_dir.join("eccentricity.csv");
+            let mut wtr = Writer::from_path(&eccentricity_path)?;
+            for row in &shape.nodes {
+                wtr.serialize(row)?;
+            }
+            wtr.flush()?;
+            written.push(eccentricity_path);
+
+            let shapes_path = out_dir.join("component_shapes.csv");
+            let mut wtr = Writer::from_path(&shapes_path)?;
+            for row in &shape.components {
+                wtr.serialize(row)?;
+            }
+            wtr.flush()?;
+            written.push(shapes_path);
+        }
+
+        if let Some(stats) = &self.clustering {
+            let clustering_path = out_dir.join("clustering.csv");
+            let mut wtr = Writer::from_path(&clustering_path)?;
+            for row in &stats.nodes {
+                wtr.serialize(row)?;
+            }
+            wtr.flush()?;
+            written.push(clustering_path);
+        }
+
+        if let Some(blocs) = &self.area_communities {
+            let membership_path = out_dir.join("area_communities.csv");
+            let mut wtr = Writer::from_path(&membership_path)?;
+            wtr.write_record(["area", "community"])?;
+            for community in &blocs.communities {
+                for area in &community.areas {
+                    wtr.write_record([area.as_str(), community.id.to_string().as_str()])?;
+                }
+            }
+            wtr.flush()?;
+            written.push(membership_path);
+
+            let communities_path = out_dir.join("communities.json");
+            fs::write(&communities_path, serde_json::to_string_pretty(blocs)?)?;
+            written.push(communities_path);
+        }
+
+        if let Some(quality) = &self.quality {
+            written.push(quality.write(out_dir)?);
+        }
+
+        Ok(written)
+    }
+}